@@ -0,0 +1,317 @@
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+// Sticks have to be pushed this far to count as a direction in menus.
+const STICK_PRESS_THRESHOLD: f32 = 0.5;
+const STICK_DEADZONE: f32 = 0.2;
+
+/// What the game reacts to, gameplay and menus read these instead of keys and buttons.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Pause,
+    Confirm,
+    Cancel,
+}
+
+/// Every action has a primary and a secondary key.
+pub const KEY_SLOTS: usize = 2;
+
+pub const ACTIONS: [Action; 7] = [
+    Action::Up,
+    Action::Down,
+    Action::Left,
+    Action::Right,
+    Action::Pause,
+    Action::Confirm,
+    Action::Cancel,
+];
+
+impl Action {
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Up => "Up",
+            Action::Down => "Down",
+            Action::Left => "Left",
+            Action::Right => "Right",
+            Action::Pause => "Pause",
+            Action::Confirm => "Confirm",
+            Action::Cancel => "Cancel",
+        }
+    }
+
+    /// Pause is only read in game and Cancel only in menus, except in the pause menu where both
+    /// resume, so they can share a key. Every other pair would have one key do two things.
+    fn can_share_key(self, other: Action) -> bool {
+        matches!(
+            (self, other),
+            (Action::Pause, Action::Cancel) | (Action::Cancel, Action::Pause)
+        )
+    }
+
+    /// Gamepad buttons aren't rebindable.
+    fn gamepad_button(self) -> GamepadButtonType {
+        match self {
+            Action::Up => GamepadButtonType::DPadUp,
+            Action::Down => GamepadButtonType::DPadDown,
+            Action::Left => GamepadButtonType::DPadLeft,
+            Action::Right => GamepadButtonType::DPadRight,
+            Action::Pause => GamepadButtonType::Start,
+            Action::Confirm => GamepadButtonType::South,
+            Action::Cancel => GamepadButtonType::East,
+        }
+    }
+}
+
+/// Keys for every action, saved with the settings.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    up: Vec<KeyCode>,
+    down: Vec<KeyCode>,
+    left: Vec<KeyCode>,
+    right: Vec<KeyCode>,
+    pause: Vec<KeyCode>,
+    confirm: Vec<KeyCode>,
+    cancel: Vec<KeyCode>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            up: vec![KeyCode::Up, KeyCode::W],
+            down: vec![KeyCode::Down, KeyCode::S],
+            left: vec![KeyCode::Left, KeyCode::A],
+            right: vec![KeyCode::Right, KeyCode::D],
+            pause: vec![KeyCode::Escape, KeyCode::Space],
+            confirm: vec![KeyCode::Return],
+            cancel: vec![KeyCode::Escape, KeyCode::Back],
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        match action {
+            Action::Up => &self.up,
+            Action::Down => &self.down,
+            Action::Left => &self.left,
+            Action::Right => &self.right,
+            Action::Pause => &self.pause,
+            Action::Confirm => &self.confirm,
+            Action::Cancel => &self.cancel,
+        }
+    }
+
+    fn keys_mut(&mut self, action: Action) -> &mut Vec<KeyCode> {
+        match action {
+            Action::Up => &mut self.up,
+            Action::Down => &mut self.down,
+            Action::Left => &mut self.left,
+            Action::Right => &mut self.right,
+            Action::Pause => &mut self.pause,
+            Action::Confirm => &mut self.confirm,
+            Action::Cancel => &mut self.cancel,
+        }
+    }
+
+    /// Puts `key` in one slot of the action, keeping its other key. Actions that can't share the
+    /// key lose it, and a key already in the other slot swaps places with the replaced one.
+    pub fn rebind(&mut self, action: Action, slot: usize, key: KeyCode) {
+        for other in ACTIONS {
+            if other != action && !action.can_share_key(other) {
+                self.keys_mut(other).retain(|&bound| bound != key);
+            }
+        }
+        let keys = self.keys_mut(action);
+        let replaced = keys.get(slot).copied();
+        if let Some(index) = keys.iter().position(|&bound| bound == key) {
+            match replaced {
+                Some(replaced) => keys[index] = replaced,
+                None => {
+                    keys.remove(index);
+                }
+            }
+        }
+        if slot < keys.len() {
+            keys[slot] = key;
+        } else {
+            keys.push(key);
+        }
+    }
+}
+
+/// State of every action this frame, from the keyboard and all connected gamepads.
+#[derive(Resource, Default)]
+pub struct ActionInput {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    move_axis: Vec2,
+}
+
+impl ActionInput {
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    /// Movement direction, each axis goes from -1 to 1.
+    pub fn move_axis(&self) -> Vec2 {
+        self.move_axis
+    }
+}
+
+fn stick_actions(stick: Vec2) -> impl Iterator<Item = Action> {
+    [
+        (stick.y > STICK_PRESS_THRESHOLD, Action::Up),
+        (stick.y < -STICK_PRESS_THRESHOLD, Action::Down),
+        (stick.x < -STICK_PRESS_THRESHOLD, Action::Left),
+        (stick.x > STICK_PRESS_THRESHOLD, Action::Right),
+    ]
+    .into_iter()
+    .filter_map(|(pushed, action)| pushed.then_some(action))
+}
+
+/// Presses are found by comparing with the last frame, so sticks pushed past the threshold act
+/// like buttons in menus.
+pub fn update_action_input(
+    settings: Res<Settings>,
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    mut action_input: ResMut<ActionInput>,
+) {
+    let mut pressed = HashSet::default();
+    let mut stick = Vec2::ZERO;
+    for action in ACTIONS {
+        let key_pressed =
+            keyboard_input.any_pressed(settings.key_bindings.keys(action).iter().copied());
+        let button_pressed = gamepads.iter().any(|gamepad| {
+            gamepad_buttons.pressed(GamepadButton::new(gamepad, action.gamepad_button()))
+        });
+        if key_pressed || button_pressed {
+            pressed.insert(action);
+        }
+    }
+    let direction = |negative, positive| {
+        let mut value = 0.0;
+        if pressed.contains(&negative) {
+            value -= 1.0;
+        }
+        if pressed.contains(&positive) {
+            value += 1.0;
+        }
+        value
+    };
+    let buttons_axis = Vec2::new(
+        direction(Action::Left, Action::Right),
+        direction(Action::Down, Action::Up),
+    );
+    for gamepad in gamepads.iter() {
+        let axis = |axis_type| {
+            gamepad_axes
+                .get(GamepadAxis::new(gamepad, axis_type))
+                .unwrap_or(0.0)
+        };
+        let gamepad_stick = Vec2::new(
+            axis(GamepadAxisType::LeftStickX),
+            axis(GamepadAxisType::LeftStickY),
+        );
+        if gamepad_stick.length() > stick.length() {
+            stick = gamepad_stick;
+        }
+    }
+    pressed.extend(stick_actions(stick));
+
+    // Analog sticks keep their precision unless keys or buttons are held.
+    action_input.move_axis = if buttons_axis == Vec2::ZERO && stick.length() > STICK_DEADZONE {
+        stick
+    } else {
+        buttons_axis
+    };
+    action_input.just_pressed = pressed.difference(&action_input.pressed).copied().collect();
+    action_input.pressed = pressed;
+}
+
+pub struct ActionsPlugin;
+
+impl Plugin for ActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActionInput>().add_system(
+            update_action_input
+                .after(InputSystem)
+                .in_base_set(CoreSet::PreUpdate),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_keys_do_one_thing_each() {
+        let bindings = KeyBindings::default();
+        for action in ACTIONS {
+            for other in ACTIONS {
+                if action == other || action.can_share_key(other) {
+                    continue;
+                }
+                let shared = bindings
+                    .keys(action)
+                    .iter()
+                    .find(|key| bindings.keys(other).contains(key));
+                assert_eq!(shared, None, "{action:?} and {other:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn rebinding_a_slot_keeps_the_other_key() {
+        let mut bindings = KeyBindings::default();
+        bindings.rebind(Action::Up, 1, KeyCode::I);
+        assert_eq!(bindings.keys(Action::Up), [KeyCode::Up, KeyCode::I]);
+        bindings.rebind(Action::Confirm, 1, KeyCode::E);
+        assert_eq!(
+            bindings.keys(Action::Confirm),
+            [KeyCode::Return, KeyCode::E]
+        );
+    }
+
+    #[test]
+    fn rebinding_takes_the_key_from_conflicting_actions() {
+        let mut bindings = KeyBindings::default();
+        bindings.rebind(Action::Confirm, 1, KeyCode::Space);
+        assert_eq!(bindings.keys(Action::Pause), [KeyCode::Escape]);
+        bindings.rebind(Action::Up, 0, KeyCode::S);
+        assert_eq!(bindings.keys(Action::Down), [KeyCode::Down]);
+    }
+
+    #[test]
+    fn pause_and_cancel_keep_sharing_escape() {
+        let mut bindings = KeyBindings::default();
+        bindings.rebind(Action::Cancel, 1, KeyCode::Escape);
+        assert_eq!(
+            bindings.keys(Action::Cancel),
+            [KeyCode::Back, KeyCode::Escape]
+        );
+        assert_eq!(
+            bindings.keys(Action::Pause),
+            [KeyCode::Escape, KeyCode::Space]
+        );
+    }
+
+    #[test]
+    fn rebinding_to_the_other_slot_swaps_the_keys() {
+        let mut bindings = KeyBindings::default();
+        bindings.rebind(Action::Left, 0, KeyCode::A);
+        assert_eq!(bindings.keys(Action::Left), [KeyCode::A, KeyCode::Left]);
+    }
+}
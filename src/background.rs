@@ -0,0 +1,154 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::camera::MainCamera;
+
+const CHUNK_SIZE: f32 = 1024.0;
+// How many chunks are kept loaded in each direction around the camera's chunk.
+const CHUNK_RADIUS: i32 = 1;
+const DECORATIONS_PER_CHUNK: std::ops::Range<usize> = 8..16;
+
+#[derive(Resource)]
+pub struct BackgroundSettings {
+    pub decorations: bool,
+}
+
+impl Default for BackgroundSettings {
+    fn default() -> Self {
+        Self { decorations: true }
+    }
+}
+
+#[derive(Component)]
+struct BackgroundChunk;
+
+#[derive(Resource, Default, Deref, DerefMut)]
+struct LoadedChunks(HashMap<IVec2, Entity>);
+
+struct Decoration {
+    color: Color,
+    size: Vec2,
+}
+
+const DECORATIONS: [Decoration; 3] = [
+    // Rock
+    Decoration {
+        color: Color::rgb(0.45, 0.45, 0.5),
+        size: Vec2::new(12.0, 9.0),
+    },
+    // Pebble
+    Decoration {
+        color: Color::rgb(0.6, 0.6, 0.62),
+        size: Vec2::new(4.0, 4.0),
+    },
+    // Bush
+    Decoration {
+        color: Color::rgb(0.15, 0.35, 0.15),
+        size: Vec2::new(16.0, 10.0),
+    },
+];
+
+fn chunk_rng(coord: IVec2) -> StdRng {
+    let seed = (coord.x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (coord.y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    StdRng::seed_from_u64(seed)
+}
+
+fn spawn_decorations(parent: &mut ChildBuilder, coord: IVec2) {
+    let mut rng = chunk_rng(coord);
+    let half_chunk = CHUNK_SIZE / 2.0;
+    for _ in 0..rng.gen_range(DECORATIONS_PER_CHUNK) {
+        let decoration = &DECORATIONS[rng.gen_range(0..DECORATIONS.len())];
+        let position = Vec2::new(
+            rng.gen_range(-half_chunk..half_chunk),
+            rng.gen_range(-half_chunk..half_chunk),
+        );
+        parent.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: decoration.color,
+                custom_size: Some(decoration.size),
+                ..default()
+            },
+            transform: Transform::from_translation(position.extend(0.1)),
+            ..default()
+        });
+    }
+}
+
+fn stream_background(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<BackgroundSettings>,
+    mut loaded_chunks: ResMut<LoadedChunks>,
+    camera_query: Query<&Transform, With<MainCamera>>,
+    mut chunk_query: Query<&mut Transform, (With<BackgroundChunk>, Without<MainCamera>)>,
+) {
+    let Some(camera_transform) = camera_query.iter().next() else { return };
+    let center = (camera_transform.translation.truncate() / CHUNK_SIZE)
+        .round()
+        .as_ivec2();
+
+    let mut wanted_chunks = Vec::new();
+    for x in -CHUNK_RADIUS..=CHUNK_RADIUS {
+        for y in -CHUNK_RADIUS..=CHUNK_RADIUS {
+            wanted_chunks.push(center + IVec2::new(x, y));
+        }
+    }
+
+    let stale_chunks: Vec<IVec2> = loaded_chunks
+        .keys()
+        .filter(|coord| !wanted_chunks.contains(coord))
+        .copied()
+        .collect();
+    let mut recyclable_entities: Vec<Entity> = stale_chunks
+        .iter()
+        .filter_map(|coord| loaded_chunks.remove(coord))
+        .collect();
+
+    for coord in wanted_chunks {
+        if loaded_chunks.contains_key(&coord) {
+            continue;
+        }
+        let translation = (coord.as_vec2() * CHUNK_SIZE).extend(0.0);
+        let chunk_entity = match recyclable_entities.pop() {
+            Some(chunk_entity) => {
+                let Ok(mut transform) = chunk_query.get_mut(chunk_entity) else { continue };
+                transform.translation = translation;
+                commands.entity(chunk_entity).despawn_descendants();
+                chunk_entity
+            }
+            None => commands
+                .spawn((
+                    BackgroundChunk,
+                    SpriteBundle {
+                        texture: asset_server.load("background.png"),
+                        transform: Transform::from_translation(translation),
+                        ..default()
+                    },
+                ))
+                .id(),
+        };
+        if settings.decorations {
+            commands
+                .entity(chunk_entity)
+                .with_children(|parent| spawn_decorations(parent, coord));
+        }
+        loaded_chunks.insert(coord, chunk_entity);
+    }
+
+    for chunk_entity in recyclable_entities {
+        commands.entity(chunk_entity).despawn_recursive();
+    }
+}
+
+pub struct BackgroundPlugin;
+
+impl Plugin for BackgroundPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BackgroundSettings>()
+            .init_resource::<LoadedChunks>()
+            .add_system(stream_background);
+    }
+}
@@ -1,33 +1,46 @@
 use bevy::prelude::*;
 
+use crate::settings::Settings;
+
+// The track is mixed loud, this is its volume at 100% in the options.
+const BGM_VOLUME: f32 = 0.08;
+
 pub struct BgmPlugin;
 
 #[derive(Resource)]
-struct Bgm(Handle<AudioSource>);
+struct Bgm(Handle<AudioSink>);
 
-fn load_bgm(mut commands: Commands, asset_server: Res<AssetServer>) {
+/// Loops for the whole session, the audio plugin holds the sound until the file is loaded.
+fn play_bgm(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    audio: Res<Audio>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    settings: Res<Settings>,
+) {
     let bgm = asset_server.load("little-dark-age.ogg");
-    commands.insert_resource(Bgm(bgm));
-}
-
-fn play_bgm(audio: Res<Audio>, bgm: Res<Bgm>, mut is_playing: Local<bool>) {
-    if *is_playing {
-        return;
-    }
-
-    audio.play_with_settings(
-        bgm.0.clone(),
+    let sink = audio.play_with_settings(
+        bgm,
         PlaybackSettings {
             repeat: true,
-            volume: 0.08,
+            volume: BGM_VOLUME * settings.music_volume,
             ..default()
         },
     );
-    *is_playing = true;
+    commands.insert_resource(Bgm(audio_sinks.get_handle(sink)));
+}
+
+fn update_bgm_volume(settings: Res<Settings>, bgm: Res<Bgm>, audio_sinks: Res<Assets<AudioSink>>) {
+    if !settings.is_changed() {
+        return;
+    }
+    let Some(sink) = audio_sinks.get(&bgm.0) else { return };
+    sink.set_volume(BGM_VOLUME * settings.music_volume);
 }
 
 impl Plugin for BgmPlugin {
     fn build(&self, app: &mut App) {
-        app.add_startup_system(load_bgm).add_system(play_bgm);
+        app.add_startup_system(play_bgm)
+            .add_system(update_bgm_volume);
     }
 }
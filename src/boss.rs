@@ -0,0 +1,260 @@
+use bevy::prelude::*;
+use std::f32::consts::TAU;
+
+use crate::{
+    enemies::{spawn_enemy, Elite, Enemy, EnemyAtlases, EnemyKind},
+    enemy_movement::spawn_enemy_projectile,
+    simulation::{GameClock, SimulationSet},
+    status_effects::{StatusEffect, StatusEffects, StatusKind},
+};
+
+const BOSS_SCALE: f32 = 2.0;
+const BOSS_PROJECTILE_SPEED: f32 = 120.0;
+const BOSS_PROJECTILE_DAMAGE: i32 = 8;
+const BOSS_PROJECTILE_EFFECT: StatusEffect = StatusEffect {
+    kind: StatusKind::Slow,
+    strength: 0.6,
+    duration: 1.5,
+};
+const BOSS_BAR_COLOR: Color = Color::rgb(0.8, 0.1, 0.2);
+
+/// Attack pattern used while the boss's hp is above `hp_fraction` of its max.
+struct BossPhase {
+    hp_fraction: f32,
+    attack_interval: f32,
+    projectiles: usize,
+    minions: usize,
+    speed_multiplier: f32,
+}
+
+const BOSS_PHASES: [BossPhase; 3] = [
+    BossPhase {
+        hp_fraction: 0.66,
+        attack_interval: 3.0,
+        projectiles: 8,
+        minions: 0,
+        speed_multiplier: 1.0,
+    },
+    BossPhase {
+        hp_fraction: 0.33,
+        attack_interval: 2.5,
+        projectiles: 12,
+        minions: 6,
+        speed_multiplier: 1.0,
+    },
+    // Enraged: fast spiralling bursts until it dies.
+    BossPhase {
+        hp_fraction: 0.0,
+        attack_interval: 1.0,
+        projectiles: 10,
+        minions: 0,
+        speed_multiplier: 1.5,
+    },
+];
+
+#[derive(Component)]
+pub struct Boss {
+    name: String,
+    max_hp: i32,
+    base_speed: f32,
+    phase: usize,
+    attack_timer: Timer,
+    // Rotates every burst so consecutive bursts spiral.
+    burst_angle: f32,
+}
+
+#[derive(Component)]
+pub struct BossHpBar;
+
+#[derive(Component)]
+struct BossHpBarFill;
+
+#[derive(Component)]
+struct BossName;
+
+pub fn make_boss(
+    commands: &mut Commands,
+    boss_entity: Entity,
+    kind: EnemyKind,
+    name: &str,
+    hp: i32,
+) {
+    let enemy = Enemy {
+        hp,
+        ..Enemy::new(kind)
+    };
+    commands.entity(boss_entity).insert((
+        Boss {
+            name: name.to_string(),
+            max_hp: hp,
+            base_speed: enemy.speed,
+            phase: 0,
+            attack_timer: Timer::from_seconds(BOSS_PHASES[0].attack_interval, TimerMode::Repeating),
+            burst_angle: 0.0,
+        },
+        Elite { scale: BOSS_SCALE },
+        enemy,
+    ));
+}
+
+fn boss_attacks(
+    mut commands: Commands,
+    time: Res<GameClock>,
+    asset_server: Res<AssetServer>,
+    enemy_atlases: Res<EnemyAtlases>,
+    mut boss_query: Query<(&mut Boss, &mut Enemy, &Transform, &StatusEffects)>,
+) {
+    for (mut boss, mut enemy, transform, effects) in &mut boss_query {
+        if effects.is_frozen() {
+            continue;
+        }
+        let hp_fraction = enemy.hp as f32 / boss.max_hp as f32;
+        let Some(phase_index) = BOSS_PHASES
+            .iter()
+            .position(|phase| hp_fraction > phase.hp_fraction)
+        else {
+            continue;
+        };
+        let phase = &BOSS_PHASES[phase_index];
+        if boss.phase != phase_index {
+            boss.phase = phase_index;
+            boss.attack_timer = Timer::from_seconds(phase.attack_interval, TimerMode::Repeating);
+            enemy.speed = boss.base_speed * phase.speed_multiplier;
+        }
+
+        boss.attack_timer.tick(time.delta());
+        if !boss.attack_timer.just_finished() {
+            continue;
+        }
+
+        for index in 0..phase.projectiles {
+            let direction =
+                Vec2::from_angle(boss.burst_angle + index as f32 * TAU / phase.projectiles as f32);
+            spawn_enemy_projectile(
+                &mut commands,
+                &asset_server,
+                transform.translation,
+                direction * BOSS_PROJECTILE_SPEED,
+                BOSS_PROJECTILE_DAMAGE,
+                Some(BOSS_PROJECTILE_EFFECT),
+            );
+        }
+        boss.burst_angle += TAU / (phase.projectiles * 3) as f32;
+
+        for index in 0..phase.minions {
+            let offset = Vec2::from_angle(index as f32 * TAU / phase.minions as f32) * 40.0;
+            spawn_enemy(
+                &mut commands,
+                &enemy_atlases,
+                EnemyKind::Soyjak,
+                transform.translation + offset.extend(0.0),
+            );
+        }
+    }
+}
+
+fn add_boss_hp_bar(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    boss_query: Query<&Boss, Added<Boss>>,
+    bar_query: Query<(), With<BossHpBar>>,
+) {
+    let Some(boss) = boss_query.iter().next() else { return };
+    if !bar_query.is_empty() {
+        return;
+    }
+
+    commands
+        .spawn((
+            BossHpBar,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        top: Val::Px(24.0),
+                        left: Val::Percent(10.0),
+                        ..default()
+                    },
+                    size: Size::width(Val::Percent(80.0)),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|top_level| {
+            top_level.spawn((
+                BossName,
+                TextBundle::from_section(
+                    boss.name.clone(),
+                    TextStyle {
+                        font: asset_server.load("pixel_font.ttf"),
+                        font_size: 16.0,
+                        ..default()
+                    },
+                ),
+            ));
+            top_level
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.0), Val::Px(10.0)),
+                        margin: UiRect::top(Val::Px(4.0)),
+                        ..default()
+                    },
+                    background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                    ..default()
+                })
+                .with_children(|bar| {
+                    bar.spawn((
+                        BossHpBarFill,
+                        NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                                ..default()
+                            },
+                            background_color: BOSS_BAR_COLOR.into(),
+                            ..default()
+                        },
+                    ));
+                });
+        });
+}
+
+fn animate_boss_hp_bar(
+    mut commands: Commands,
+    boss_query: Query<(&Boss, &Enemy)>,
+    bar_query: Query<Entity, With<BossHpBar>>,
+    mut fill_query: Query<&mut Style, With<BossHpBarFill>>,
+    mut name_query: Query<&mut Text, With<BossName>>,
+) {
+    let Some(bar_entity) = bar_query.iter().next() else { return };
+    // The bar follows whichever boss is still alive and goes away with the last one.
+    let Some((boss, enemy)) = boss_query.iter().next() else {
+        commands.entity(bar_entity).despawn_recursive();
+        return;
+    };
+    for mut style in &mut fill_query {
+        style.size.width = Val::Percent((enemy.hp as f32 / boss.max_hp as f32).max(0.0) * 100.0);
+    }
+    for mut text in &mut name_query {
+        if text.sections[0].value != boss.name {
+            text.sections[0].value = boss.name.clone();
+        }
+    }
+}
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (
+                boss_attacks,
+                add_boss_hp_bar,
+                animate_boss_hp_bar.after(add_boss_hp_bar),
+            )
+                .in_set(SimulationSet),
+        );
+    }
+}
@@ -1,4 +1,9 @@
-use crate::Player;
+use crate::{
+    player_damage::PlayerDamagedEvent,
+    settings::Settings,
+    simulation::{simulation_running, GameClock, SimulationSet},
+    Player,
+};
 use bevy::{
     prelude::*,
     render::{
@@ -11,13 +16,28 @@ use bevy::{
     },
     sprite::MaterialMesh2dBundle,
 };
+use rand::Rng;
 
-#[derive(Resource)]
-pub struct MainRender(pub Handle<Image>);
+const MAX_SHAKE_OFFSET: f32 = 8.0;
+// Trauma lost per second.
+const TRAUMA_DECAY: f32 = 1.5;
+const PLAYER_HIT_TRAUMA: f32 = 0.4;
 
 #[derive(Component)]
 pub struct MainCamera;
 
+/// Camera shake left, from 0 to 1. The offset grows with its square so small amounts barely shake.
+#[derive(Resource, Default)]
+pub struct ScreenShake {
+    trauma: f32,
+}
+
+impl ScreenShake {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.0);
+    }
+}
+
 #[derive(Component)]
 pub struct FinalCamera;
 
@@ -66,7 +86,7 @@ fn setup_camera(
     let quad_handle = meshes.add(Mesh::from(shape::Quad::new(Vec2::new(500.0, 500.0))));
 
     let material_handle = materials.add(ColorMaterial {
-        texture: Some(image_handle.clone()),
+        texture: Some(image_handle),
         ..default()
     });
 
@@ -86,8 +106,6 @@ fn setup_camera(
         Name::new("Base Render"),
     ));
 
-    commands.insert_resource(MainRender(image_handle));
-
     let mut camera = Camera2dBundle::default();
     camera.camera.order = 999;
     camera.projection.scaling_mode = ScalingMode::AutoMin {
@@ -117,11 +135,45 @@ fn follow_player(
     camera_transform.translation.y = player_transform.translation.y;
 }
 
+fn shake_on_player_hit(
+    mut damaged_reader: EventReader<PlayerDamagedEvent>,
+    mut screen_shake: ResMut<ScreenShake>,
+) {
+    if damaged_reader.iter().count() > 0 {
+        screen_shake.add_trauma(PLAYER_HIT_TRAUMA);
+    }
+}
+
+/// Runs after `follow_player` so the offset never builds up.
+fn shake_camera(
+    time: Res<GameClock>,
+    settings: Res<Settings>,
+    mut screen_shake: ResMut<ScreenShake>,
+    mut camera_transform_query: Query<&mut Transform, With<MainCamera>>,
+) {
+    if screen_shake.trauma <= 0.0 {
+        return;
+    }
+    let strength = screen_shake.trauma.powi(2) * MAX_SHAKE_OFFSET * settings.screen_shake;
+    screen_shake.trauma = (screen_shake.trauma - TRAUMA_DECAY * time.delta_seconds()).max(0.0);
+    let Some(mut camera_transform) = camera_transform_query.iter_mut().next() else { return };
+
+    let mut rng = rand::thread_rng();
+    let offset = Vec2::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)) * strength;
+    camera_transform.translation += offset.extend(0.0);
+}
+
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_startup_system(setup_camera)
-            .add_system(follow_player.in_base_set(CoreSet::PostUpdate));
+        app.init_resource::<ScreenShake>()
+            .add_startup_system(setup_camera)
+            .add_system(shake_on_player_hit.in_set(SimulationSet))
+            .add_systems(
+                (follow_player, shake_camera.after(follow_player))
+                    .distributive_run_if(simulation_running)
+                    .in_base_set(CoreSet::PostUpdate),
+            );
     }
 }
@@ -1,53 +1,228 @@
 use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use std::f32::consts::TAU;
 
-use crate::Player;
+use crate::{
+    effects::DamageType,
+    passive_items::PassiveKind,
+    simulation::{GameClock, SimulationSet},
+    status_effects::{StatusEffect, StatusKind},
+    weapon::{
+        AddWeapon, Attack, Evolution, FireContext, ProjectileTemplate, Targeting, WeaponAtlases,
+        WeaponBehaviour, WeaponDefinition, WeaponKind, WeaponLevel,
+    },
+    Player,
+};
 
-pub struct AddCatWeaponEvent;
+const CAT_ORBIT_RADIUS: f32 = 60.0;
+// How far zoomies cats swing in and out of the orbit.
+const ZOOMIES_PULSE: f32 = 35.0;
+// How long a cat waits before it can scratch the same enemy again.
+const CAT_HIT_COOLDOWN: f32 = 0.5;
+const CAT_FRAMES: [usize; 2] = [26, 27];
 
 #[derive(Component)]
-struct CatWeapon;
+struct Cat {
+    start_angle: f32,
+    orbit_speed: f32,
+    pulse: f32,
+}
+
+#[derive(Component, Deref, DerefMut)]
+struct CatAnimationTimer(Timer);
+
+/// Seconds the cats have been running around the player.
+#[derive(Resource, Default)]
+pub struct CatOrbitTime(f32);
 
-fn spawn_cat_weapon(
-    mut commands: Commands,
+fn load_cat_atlas(
     asset_server: Res<AssetServer>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    mut add_cat_weapon_reader: EventReader<AddCatWeaponEvent>,
-    player_transform_query: Query<&Transform, With<Player>>,
-    mut has_spawned: Local<bool>,
+    mut weapon_atlases: ResMut<WeaponAtlases>,
 ) {
-    if *has_spawned {
-        return;
-    }
-
-    if add_cat_weapon_reader.iter().next().is_none() {
-        return;
-    }
-
-    let Some(player_transform) = player_transform_query.iter().next() else { return };
-
-    *has_spawned = true;
     let spritesheet_handle = asset_server.load("cat.png");
     let texture_atlas =
         TextureAtlas::from_grid(spritesheet_handle, Vec2::new(32.0, 32.0), 8, 4, None, None);
-    commands.spawn((
-        CatWeapon,
-        SpriteSheetBundle {
-            sprite: TextureAtlasSprite::new(27),
-            texture_atlas: texture_atlases.add(texture_atlas),
-            transform: Transform {
-                translation: player_transform.translation,
+    weapon_atlases
+        .0
+        .insert(WeaponKind::Cat, texture_atlases.add(texture_atlas));
+}
+
+fn spawn_cats(commands: &mut Commands, context: &FireContext) {
+    spawn_orbiting_cats(commands, context, 0.0);
+}
+
+fn spawn_zoomies(commands: &mut Commands, context: &FireContext) {
+    spawn_orbiting_cats(commands, context, ZOOMIES_PULSE);
+}
+
+fn spawn_orbiting_cats(commands: &mut Commands, context: &FireContext, pulse: f32) {
+    let scale = context.area * context.projectile.scale;
+    for direction in context.directions.iter() {
+        commands.spawn((
+            // Cats keep circling through enemies and scratch them again after a cooldown.
+            context.projectile_bundle(Attack {
+                pierce: None,
+                rehit_cooldown: Some(CAT_HIT_COOLDOWN),
+                ..context.attack()
+            }),
+            Cat {
+                start_angle: direction.y.atan2(direction.x),
+                orbit_speed: context.stats.speed,
+                pulse,
+            },
+            SpriteSheetBundle {
+                sprite: TextureAtlasSprite {
+                    color: context.projectile.color,
+                    ..TextureAtlasSprite::new(CAT_FRAMES[0])
+                },
+                // Zoomies are cats too.
+                texture_atlas: context.atlases.0[&WeaponKind::Cat].clone(),
+                transform: Transform {
+                    translation: context.origin,
+                    scale: Vec3::new(scale, scale, 1.0),
+                    ..default()
+                },
                 ..default()
             },
-            ..default()
-        },
-    ));
+            CatAnimationTimer(Timer::from_seconds(0.15, TimerMode::Repeating)),
+            RigidBody::KinematicPositionBased,
+        ));
+    }
+}
+
+fn orbit_cats(
+    time: Res<GameClock>,
+    player_transform_query: Query<&Transform, With<Player>>,
+    mut cat_query: Query<(&Cat, &mut Transform, &mut TextureAtlasSprite), Without<Player>>,
+    mut orbit_time: ResMut<CatOrbitTime>,
+) {
+    let Some(player_transform) = player_transform_query.iter().next() else { return };
+
+    orbit_time.0 += time.delta_seconds();
+    let orbit_time = orbit_time.0;
+    for (cat, mut cat_transform, mut sprite) in cat_query.iter_mut() {
+        let angle = (cat.start_angle + cat.orbit_speed * orbit_time) % TAU;
+        let radius = CAT_ORBIT_RADIUS + cat.pulse * (3.0 * orbit_time + cat.start_angle).sin();
+        let offset = Vec2::new(angle.cos(), angle.sin()) * radius;
+        cat_transform.translation = player_transform.translation + offset.extend(0.0);
+        // The cats run counter-clockwise, so they face left on the top half of the circle.
+        sprite.flip_x = angle.sin() > 0.0;
+    }
+}
+
+fn animate_cats(
+    time: Res<GameClock>,
+    mut cat_query: Query<(&mut CatAnimationTimer, &mut TextureAtlasSprite), With<Cat>>,
+) {
+    for (mut timer, mut sprite) in cat_query.iter_mut() {
+        timer.tick(time.delta());
+        if timer.just_finished() {
+            let frame = CAT_FRAMES
+                .iter()
+                .position(|frame| *frame == sprite.index)
+                .unwrap_or(0);
+            sprite.index = CAT_FRAMES[(frame + 1) % CAT_FRAMES.len()];
+        }
+    }
+}
+
+fn cat_level(
+    cats: usize,
+    orbit_speed: f32,
+    base_dmg: i32,
+    extra_dmg: i32,
+    description: &'static str,
+) -> WeaponLevel {
+    WeaponLevel {
+        base_dmg,
+        extra_dmg,
+        cooldown: 0.0,
+        amount: cats,
+        speed: orbit_speed,
+        pierce: 0,
+        description,
+    }
 }
 
 pub struct CatWeaponPlugin;
 
 impl Plugin for CatWeaponPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<AddCatWeaponEvent>()
-            .add_system(spawn_cat_weapon);
+        app.add_weapon(WeaponDefinition {
+            kind: WeaponKind::Cat,
+            name: "Cat",
+            description: "A cat circles you and scratches enemies.",
+            icon: "cat.png",
+            icon_color: Color::WHITE,
+            weight: 3.0,
+            targeting: Targeting::Around,
+            projectile: ProjectileTemplate {
+                texture: None,
+                color: Color::WHITE,
+                scale: 1.0,
+                collider_radius: 10.0,
+                spread: 0.0,
+                lifetime: None,
+                max_range: None,
+                effect: None,
+                knockback: 150.0,
+                damage_type: DamageType::Physical,
+                crit_chance: 0.1,
+                crit_multiplier: 2.0,
+            },
+            levels: vec![
+                cat_level(1, 2.0, 5, 3, "A cat circles you and scratches enemies."),
+                cat_level(2, 2.0, 5, 3, "One more cat."),
+                cat_level(2, 2.5, 7, 3, "Cats run faster and scratch harder."),
+                cat_level(3, 2.5, 7, 3, "One more cat."),
+                cat_level(3, 3.0, 9, 4, "Cats run faster and scratch harder."),
+                cat_level(4, 3.5, 11, 4, "One more cat, and they are even faster."),
+            ],
+            behaviour: WeaponBehaviour::Persistent(spawn_cats),
+            evolution: Some(Evolution {
+                passive: PassiveKind::Wings,
+                into: WeaponKind::Zoomies,
+            }),
+        })
+        .add_weapon(WeaponDefinition {
+            kind: WeaponKind::Zoomies,
+            name: "Zoomies",
+            description: "A pack of frantic cats dashes in and out around you.",
+            icon: "cat.png",
+            icon_color: Color::rgb(1.0, 0.7, 0.3),
+            weight: 0.0,
+            targeting: Targeting::Around,
+            projectile: ProjectileTemplate {
+                texture: None,
+                color: Color::rgb(1.0, 0.7, 0.3),
+                scale: 1.0,
+                collider_radius: 12.0,
+                spread: 0.0,
+                lifetime: None,
+                max_range: None,
+                effect: Some(StatusEffect {
+                    kind: StatusKind::Slow,
+                    strength: 0.5,
+                    duration: 2.0,
+                }),
+                knockback: 250.0,
+                damage_type: DamageType::Physical,
+                crit_chance: 0.2,
+                crit_multiplier: 2.5,
+            },
+            levels: vec![cat_level(
+                6,
+                4.5,
+                16,
+                6,
+                "A pack of frantic cats dashes in and out around you.",
+            )],
+            behaviour: WeaponBehaviour::Persistent(spawn_zoomies),
+            evolution: None,
+        })
+        .init_resource::<CatOrbitTime>()
+        .add_startup_system(load_cat_atlas)
+        .add_systems((orbit_cats, animate_cats).in_set(SimulationSet));
     }
 }
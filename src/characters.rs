@@ -0,0 +1,303 @@
+use bevy::prelude::*;
+
+use crate::{
+    actions::{Action, ActionInput},
+    menu_navigation::Focusable,
+    stats::{Stat, StatModifier},
+    weapon::{WeaponDefinitions, WeaponKind},
+    GameState,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CharacterKind {
+    Chudjak,
+    Soyjak,
+    Cobson,
+}
+
+pub const CHARACTER_KINDS: [CharacterKind; 3] = [
+    CharacterKind::Chudjak,
+    CharacterKind::Soyjak,
+    CharacterKind::Cobson,
+];
+
+pub struct CharacterDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub spritesheet: &'static str,
+    pub tile_size: Vec2,
+    /// Frames are laid out in a single row, characters with one frame aren't animated.
+    pub frames: usize,
+    /// Frame shown while standing still.
+    pub idle_frame: usize,
+    pub max_hp: i32,
+    pub starting_weapon: WeaponKind,
+    /// Replace the default base value of these stats.
+    pub base_stats: &'static [(Stat, f32)],
+    /// Applied once at the start of the run, like a passive item that can't be levelled.
+    pub bonus: StatModifier,
+}
+
+const CHUDJAK: CharacterDefinition = CharacterDefinition {
+    name: "Chudjak",
+    description: "Deals 10% more damage.",
+    spritesheet: "chudjak.png",
+    tile_size: Vec2::new(28.0, 46.0),
+    frames: 7,
+    idle_frame: 1,
+    max_hp: 100,
+    starting_weapon: WeaponKind::Fireball,
+    base_stats: &[],
+    bonus: StatModifier::Multiply(Stat::Might, 1.1),
+};
+
+const SOYJAK: CharacterDefinition = CharacterDefinition {
+    name: "Soyjak",
+    description: "Fast and frail, weapons cool down 10% faster.",
+    spritesheet: "soyjak.png",
+    tile_size: Vec2::new(28.0, 48.0),
+    frames: 1,
+    idle_frame: 0,
+    max_hp: 80,
+    starting_weapon: WeaponKind::Cat,
+    base_stats: &[(Stat::MoveSpeed, 120.0)],
+    bonus: StatModifier::Multiply(Stat::Cooldown, 0.9),
+};
+
+const COBSON: CharacterDefinition = CharacterDefinition {
+    name: "Cobson",
+    description: "Slow but sturdy, recovers 0.3 HP per second.",
+    spritesheet: "cobson.png",
+    tile_size: Vec2::new(28.0, 52.0),
+    frames: 1,
+    idle_frame: 0,
+    max_hp: 150,
+    starting_weapon: WeaponKind::Fireball,
+    base_stats: &[(Stat::MoveSpeed, 85.0), (Stat::Armor, 1.0)],
+    bonus: StatModifier::Add(Stat::Regen, 0.3),
+};
+
+impl CharacterKind {
+    pub fn definition(self) -> &'static CharacterDefinition {
+        match self {
+            CharacterKind::Chudjak => &CHUDJAK,
+            CharacterKind::Soyjak => &SOYJAK,
+            CharacterKind::Cobson => &COBSON,
+        }
+    }
+}
+
+/// The character the next run is played with.
+#[derive(Resource, Deref, DerefMut)]
+pub struct SelectedCharacter(pub CharacterKind);
+
+impl Default for SelectedCharacter {
+    fn default() -> Self {
+        Self(CharacterKind::Chudjak)
+    }
+}
+
+/// Whether picking a character starts a run or only changes the selection.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CharacterSelectMode {
+    #[default]
+    StartRun,
+    Browse,
+}
+
+#[derive(Component)]
+struct CharacterSelectScreen;
+
+#[derive(Component)]
+struct BackButton;
+
+#[derive(Component)]
+struct CharacterChoice {
+    kind: CharacterKind,
+}
+
+fn add_character_select_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    weapon_definitions: Res<WeaponDefinitions>,
+) {
+    commands
+        .spawn((
+            CharacterSelectScreen,
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    size: Size {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                    },
+                    padding: UiRect::all(Val::Px(40.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                ..default()
+            },
+        ))
+        .with_children(|top_level| {
+            top_level
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size {
+                            width: Val::Percent(100.0),
+                            height: Val::Px(40.0),
+                        },
+                        margin: UiRect {
+                            bottom: Val::Px(24.0),
+                            ..default()
+                        },
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.15, 0.15, 1.00).into(),
+                    ..default()
+                })
+                .with_children(|node| {
+                    node.spawn(TextBundle::from_section(
+                        "Choose your character",
+                        TextStyle {
+                            font: asset_server.load("pixel_font.ttf"),
+                            ..default()
+                        },
+                    ));
+                });
+            for (order, kind) in CHARACTER_KINDS.into_iter().enumerate() {
+                let character = kind.definition();
+                let weapon = weapon_definitions.get(character.starting_weapon);
+                let details = [
+                    character.description.to_string(),
+                    format!("HP: {}  Weapon: {}", character.max_hp, weapon.name),
+                ];
+                top_level
+                    .spawn((
+                        CharacterChoice { kind },
+                        Focusable { order },
+                        ButtonBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                size: Size::new(Val::Percent(100.0), Val::Auto),
+                                margin: UiRect::bottom(Val::Px(10.0)),
+                                padding: UiRect::all(Val::Px(6.0)),
+                                ..default()
+                            },
+                            background_color: Color::rgb(0.15, 0.15, 1.00).into(),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|choice| {
+                        choice.spawn(TextBundle::from_section(
+                            character.name,
+                            TextStyle {
+                                font: asset_server.load("pixel_font.ttf"),
+                                font_size: 16.0,
+                                ..default()
+                            },
+                        ));
+                        for detail in details {
+                            choice.spawn(
+                                TextBundle::from_section(
+                                    detail,
+                                    TextStyle {
+                                        font: asset_server.load("pixel_font.ttf"),
+                                        ..default()
+                                    },
+                                )
+                                .with_style(Style {
+                                    margin: UiRect::top(Val::Px(4.0)),
+                                    ..default()
+                                }),
+                            );
+                        }
+                    });
+            }
+            top_level
+                .spawn((
+                    BackButton,
+                    Focusable {
+                        order: CHARACTER_KINDS.len(),
+                    },
+                    ButtonBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(160.0), Val::Px(40.0)),
+                            margin: UiRect::top(Val::Px(14.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: Color::rgb(0.15, 0.15, 1.00).into(),
+                        ..default()
+                    },
+                ))
+                .with_children(|button| {
+                    button.spawn(TextBundle::from_section(
+                        "Back",
+                        TextStyle {
+                            font: asset_server.load("pixel_font.ttf"),
+                            ..default()
+                        },
+                    ));
+                });
+        });
+}
+
+fn handle_character_choice(
+    interaction_query: Query<(&Interaction, &CharacterChoice), Changed<Interaction>>,
+    select_mode: Res<CharacterSelectMode>,
+    mut selected_character: ResMut<SelectedCharacter>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, choice) in &interaction_query {
+        if let Interaction::Clicked = interaction {
+            selected_character.0 = choice.kind;
+            state.set(match *select_mode {
+                CharacterSelectMode::StartRun => GameState::Playing,
+                CharacterSelectMode::Browse => GameState::MainMenu,
+            });
+        }
+    }
+}
+
+fn handle_back(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    action_input: Res<ActionInput>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    let clicked = interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if clicked || action_input.just_pressed(Action::Cancel) {
+        state.set(GameState::MainMenu);
+    }
+}
+
+fn remove_character_select_screen(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<CharacterSelectScreen>>,
+) {
+    let Some(screen_entity) = screen_query.iter().next() else { return };
+    commands.entity(screen_entity).despawn_recursive();
+}
+
+pub struct CharactersPlugin;
+
+impl Plugin for CharactersPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedCharacter>()
+            .init_resource::<CharacterSelectMode>()
+            .add_system(
+                add_character_select_screen.in_schedule(OnEnter(GameState::CharacterSelect)),
+            )
+            .add_systems(
+                (handle_character_choice, handle_back).in_set(OnUpdate(GameState::CharacterSelect)),
+            )
+            .add_system(
+                remove_character_select_screen.in_schedule(OnExit(GameState::CharacterSelect)),
+            );
+    }
+}
@@ -0,0 +1,390 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+
+use crate::{
+    menu_navigation::Focusable,
+    passive_items::{PassiveItem, PassiveKind, MAX_PASSIVE_ITEMS},
+    physics_groups,
+    simulation::SimulationSet,
+    stats::{PlayerStats, Stat},
+    upgrades::{
+        choose_options, evolution_option, upgrade_options, UpgradeChosenEvent, UpgradeKind,
+        UpgradeOption, UpgradePool,
+    },
+    weapon::{Weapon, WeaponDefinitions, MAX_WEAPONS},
+    GameState, Player,
+};
+
+const CHEST_COLOR: Color = Color::rgb(1.0, 0.8, 0.2);
+// Seconds between each reward showing up on the chest screen.
+const REVEAL_INTERVAL: f32 = 0.5;
+
+#[derive(Component)]
+pub struct Chest;
+
+/// Chests that were picked up but haven't been opened yet.
+#[derive(Resource, Default)]
+pub struct UnopenedChests(u32);
+
+/// The upgrades in the chest that is currently open.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct ChestRewards(Vec<UpgradeOption>);
+
+#[derive(Component)]
+struct ChestScreen;
+
+#[derive(Component)]
+struct ChestReward {
+    reveal_at: f32,
+}
+
+#[derive(Component)]
+struct ContinueButton {
+    reveal_at: f32,
+}
+
+pub fn spawn_chest(commands: &mut Commands, asset_server: &Res<AssetServer>, position: Vec3) {
+    commands.spawn((
+        Chest,
+        SpriteBundle {
+            sprite: Sprite {
+                color: CHEST_COLOR,
+                ..default()
+            },
+            texture: asset_server.load("gem.png"),
+            transform: Transform {
+                translation: position,
+                scale: Vec3::splat(1.5),
+                ..default()
+            },
+            ..default()
+        },
+        RigidBody::Dynamic,
+        Sensor,
+        Collider::ball(10.0),
+        CollisionGroups::new(physics_groups::PICKUP_GROUP, physics_groups::PLAYER_GROUP),
+        Velocity::default(),
+    ));
+}
+
+fn pickup_chests(
+    mut commands: Commands,
+    rapier_context: Res<RapierContext>,
+    player_query: Query<Entity, With<Player>>,
+    chest_query: Query<Entity, With<Chest>>,
+    mut unopened_chests: ResMut<UnopenedChests>,
+) {
+    let Some(player_entity) = player_query.iter().next() else { return };
+    for chest_entity in &chest_query {
+        if rapier_context.intersection_pair(player_entity, chest_entity) != Some(true) {
+            continue;
+        }
+        commands.entity(chest_entity).despawn();
+        unopened_chests.0 += 1;
+    }
+}
+
+/// Runs after the gameplay systems and waits for any other queued state change, like a level up or
+/// the player dying, so picked up chests are opened one at a time once play resumes.
+fn open_chests(
+    mut unopened_chests: ResMut<UnopenedChests>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    if unopened_chests.0 == 0 || state.0.is_some() {
+        return;
+    }
+    unopened_chests.0 -= 1;
+    state.set(GameState::OpeningChest);
+}
+
+/// 1 reward most of the time, luck raises the odds of 3 or 5.
+fn roll_reward_count(luck: f32, rng: &mut impl Rng) -> usize {
+    let roll: f32 = rng.gen();
+    if roll < 0.05 * luck {
+        5
+    } else if roll < 0.25 * luck {
+        3
+    } else {
+        1
+    }
+}
+
+fn roll_chest_rewards(
+    pool: Res<UpgradePool>,
+    weapon_definitions: Res<WeaponDefinitions>,
+    weapon_query: Query<&Weapon>,
+    passive_query: Query<&PassiveItem>,
+    player_stats_query: Query<&PlayerStats>,
+    mut rewards: ResMut<ChestRewards>,
+) {
+    let mut rng = rand::thread_rng();
+    let luck = player_stats_query
+        .iter()
+        .next()
+        .map_or(1.0, |stats| stats.get(Stat::Luck));
+    let reward_count = roll_reward_count(luck, &mut rng);
+
+    rewards.clear();
+    // An evolution always takes the first slot when a weapon is ready for it.
+    let passives: Vec<PassiveKind> = passive_query.iter().map(|item| item.kind).collect();
+    if let Some(kind) = weapon_definitions.ready_evolution(weapon_query.iter(), &passives) {
+        rewards.extend(evolution_option(&weapon_definitions, kind));
+    }
+
+    let is_new_weapon = |kind: UpgradeKind| match kind {
+        UpgradeKind::Weapon(kind) => !weapon_query.iter().any(|weapon| weapon.kind == kind),
+        _ => false,
+    };
+    let is_new_passive = |kind: UpgradeKind| match kind {
+        UpgradeKind::Passive(kind) => !passives.contains(&kind),
+        _ => false,
+    };
+    let mut free_weapon_slots = MAX_WEAPONS.saturating_sub(weapon_query.iter().len());
+    let mut free_passive_slots = MAX_PASSIVE_ITEMS.saturating_sub(passives.len());
+    let mut options = upgrade_options(&pool, &weapon_definitions, &weapon_query, &passive_query);
+    // Rewards are chosen one at a time since every new item takes up a slot, once they are all
+    // used up only upgrades to owned items are left.
+    while rewards.len() < reward_count {
+        let Some(reward) = choose_options(&options, 1, &mut rng).pop() else { break };
+        options.retain(|option| option.kind != reward.kind);
+        if is_new_weapon(reward.kind) {
+            free_weapon_slots -= 1;
+            if free_weapon_slots == 0 {
+                options.retain(|option| !is_new_weapon(option.kind));
+            }
+        }
+        if is_new_passive(reward.kind) {
+            free_passive_slots -= 1;
+            if free_passive_slots == 0 {
+                options.retain(|option| !is_new_passive(option.kind));
+            }
+        }
+        rewards.push(reward);
+    }
+}
+
+fn add_chest_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    rewards: Res<ChestRewards>,
+) {
+    commands
+        .spawn((
+            ChestScreen,
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    size: Size {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                    },
+                    padding: UiRect::all(Val::Px(40.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                ..default()
+            },
+        ))
+        .with_children(|top_level| {
+            top_level
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size {
+                            width: Val::Percent(100.0),
+                            height: Val::Px(40.0),
+                        },
+                        margin: UiRect {
+                            bottom: Val::Px(24.0),
+                            ..default()
+                        },
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.6, 0.45, 0.1).into(),
+                    ..default()
+                })
+                .with_children(|node| {
+                    node.spawn(TextBundle::from_section(
+                        "Treasure!",
+                        TextStyle {
+                            font: asset_server.load("pixel_font.ttf"),
+                            ..default()
+                        },
+                    ));
+                });
+            for (index, reward) in rewards.iter().enumerate() {
+                top_level
+                    .spawn((
+                        ChestReward {
+                            reveal_at: REVEAL_INTERVAL * (index + 1) as f32,
+                        },
+                        NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Row,
+                                align_items: AlignItems::Center,
+                                size: Size::new(Val::Percent(100.0), Val::Px(36.0)),
+                                margin: UiRect::bottom(Val::Px(6.0)),
+                                padding: UiRect::horizontal(Val::Px(6.0)),
+                                ..default()
+                            },
+                            background_color: Color::rgb(0.15, 0.15, 1.00).into(),
+                            visibility: Visibility::Hidden,
+                            ..default()
+                        },
+                    ))
+                    .with_children(|reward_node| {
+                        reward_node.spawn(ImageBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(24.0), Val::Px(24.0)),
+                                margin: UiRect::right(Val::Px(8.0)),
+                                ..default()
+                            },
+                            image: asset_server.load(reward.icon).into(),
+                            background_color: reward.icon_color.into(),
+                            ..default()
+                        });
+                        reward_node.spawn(TextBundle::from_section(
+                            reward.name.clone(),
+                            TextStyle {
+                                font: asset_server.load("pixel_font.ttf"),
+                                font_size: 16.0,
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+            top_level
+                .spawn((
+                    ContinueButton {
+                        reveal_at: REVEAL_INTERVAL * (rewards.len() + 1) as f32,
+                    },
+                    Focusable { order: 0 },
+                    ButtonBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(160.0), Val::Px(40.0)),
+                            margin: UiRect::top(Val::Px(24.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: Color::rgb(0.15, 0.15, 1.00).into(),
+                        visibility: Visibility::Hidden,
+                        ..default()
+                    },
+                ))
+                .with_children(|button| {
+                    button.spawn(TextBundle::from_section(
+                        "Continue",
+                        TextStyle {
+                            font: asset_server.load("pixel_font.ttf"),
+                            ..default()
+                        },
+                    ));
+                });
+        });
+}
+
+/// Shows the rewards one after another, each one flashing white as it appears.
+fn reveal_rewards(
+    time: Res<Time>,
+    mut elapsed: Local<f32>,
+    screen_query: Query<(), Added<ChestScreen>>,
+    mut reward_query: Query<(&ChestReward, &mut Visibility, &mut BackgroundColor)>,
+    mut button_query: Query<(&ContinueButton, &mut Visibility), Without<ChestReward>>,
+) {
+    if !screen_query.is_empty() {
+        *elapsed = 0.0;
+    }
+    *elapsed += time.delta_seconds();
+
+    for (reward, mut visibility, mut background_color) in &mut reward_query {
+        if *elapsed < reward.reveal_at {
+            continue;
+        }
+        *visibility = Visibility::Inherited;
+        let flash = (1.0 - (*elapsed - reward.reveal_at) / REVEAL_INTERVAL).clamp(0.0, 1.0);
+        background_color.0 = Color::rgb(0.15 + 0.85 * flash, 0.15 + 0.85 * flash, 1.0);
+    }
+    for (button, mut visibility) in &mut button_query {
+        if *elapsed >= button.reveal_at {
+            *visibility = Visibility::Inherited;
+        }
+    }
+}
+
+type ContinueInteraction = (Changed<Interaction>, With<ContinueButton>);
+
+fn handle_continue(
+    interaction_query: Query<(&Interaction, &Visibility), ContinueInteraction>,
+    rewards: Res<ChestRewards>,
+    mut upgrade_chosen_writer: EventWriter<UpgradeChosenEvent>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, visibility) in &interaction_query {
+        if *interaction != Interaction::Clicked || *visibility == Visibility::Hidden {
+            continue;
+        }
+        for reward in rewards.iter() {
+            upgrade_chosen_writer.send(UpgradeChosenEvent(reward.kind));
+        }
+        state.set(GameState::Playing);
+    }
+}
+
+fn remove_chest_screen(mut commands: Commands, screen_query: Query<Entity, With<ChestScreen>>) {
+    let Some(screen_entity) = screen_query.iter().next() else { return };
+    commands.entity(screen_entity).despawn_recursive();
+}
+
+pub struct ChestPlugin;
+
+impl Plugin for ChestPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChestRewards>()
+            .init_resource::<UnopenedChests>()
+            .add_system(pickup_chests.in_set(SimulationSet))
+            .add_system(
+                open_chests
+                    .run_if(in_state(GameState::Playing))
+                    .in_base_set(CoreSet::PostUpdate),
+            )
+            .add_systems(
+                (roll_chest_rewards, add_chest_screen)
+                    .chain()
+                    .in_schedule(OnEnter(GameState::OpeningChest)),
+            )
+            .add_systems(
+                (reveal_rewards, handle_continue).in_set(OnUpdate(GameState::OpeningChest)),
+            )
+            .add_system(remove_chest_screen.in_schedule(OnExit(GameState::OpeningChest)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+
+    /// An rng whose `gen::<f32>()` always returns `roll`.
+    fn rolling(roll: f32) -> StepRng {
+        StepRng::new((roll as f64 * 2f64.powi(32)) as u64, 0)
+    }
+
+    #[test]
+    fn reward_count_follows_the_roll() {
+        assert_eq!(rolling(0.3).gen::<f32>(), 0.3);
+        assert_eq!(roll_reward_count(1.0, &mut rolling(0.01)), 5);
+        assert_eq!(roll_reward_count(1.0, &mut rolling(0.1)), 3);
+        assert_eq!(roll_reward_count(1.0, &mut rolling(0.3)), 1);
+    }
+
+    #[test]
+    fn luck_raises_the_reward_count() {
+        assert_eq!(roll_reward_count(2.5, &mut rolling(0.1)), 5);
+        assert_eq!(roll_reward_count(2.0, &mut rolling(0.3)), 3);
+        assert_eq!(roll_reward_count(0.0, &mut rolling(0.0)), 1);
+    }
+}
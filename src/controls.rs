@@ -0,0 +1,285 @@
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+
+use crate::{
+    actions::{update_action_input, Action, ActionInput, KeyBindings, ACTIONS, KEY_SLOTS},
+    menu_navigation::Focusable,
+    settings::Settings,
+    GameState,
+};
+
+#[derive(Component)]
+struct ControlsScreen;
+
+#[derive(Component, Clone, Copy)]
+enum ControlsButton {
+    /// One of the action's key slots.
+    Rebind(Action, usize),
+    Reset,
+    Back,
+}
+
+/// Text showing the key bound to a slot.
+#[derive(Component)]
+struct ControlValue(ControlsButton);
+
+/// The action and slot the next key press gets bound to.
+#[derive(Resource, Default)]
+struct ListeningForKey(Option<(Action, usize)>);
+
+fn control_value(
+    button: ControlsButton,
+    settings: &Settings,
+    listening: &ListeningForKey,
+) -> String {
+    match button {
+        ControlsButton::Rebind(action, slot) if listening.0 == Some((action, slot)) => {
+            "Press a key".to_string()
+        }
+        ControlsButton::Rebind(action, slot) => {
+            match settings.key_bindings.keys(action).get(slot) {
+                Some(key) => format!("{:?}", key),
+                None => "-".to_string(),
+            }
+        }
+        ControlsButton::Reset => "Reset to defaults".to_string(),
+        ControlsButton::Back => "Back".to_string(),
+    }
+}
+
+fn add_controls_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    listening: Res<ListeningForKey>,
+) {
+    commands
+        .spawn((
+            ControlsScreen,
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    size: Size {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                    },
+                    padding: UiRect::all(Val::Px(30.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                ..default()
+            },
+        ))
+        .with_children(|top_level| {
+            top_level
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size {
+                            width: Val::Percent(100.0),
+                            height: Val::Px(40.0),
+                        },
+                        margin: UiRect {
+                            bottom: Val::Px(16.0),
+                            ..default()
+                        },
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.15, 0.15, 1.00).into(),
+                    ..default()
+                })
+                .with_children(|node| {
+                    node.spawn(TextBundle::from_section(
+                        "Controls",
+                        TextStyle {
+                            font: asset_server.load("pixel_font.ttf"),
+                            ..default()
+                        },
+                    ));
+                });
+            let font = asset_server.load("pixel_font.ttf");
+            let mut order = 0;
+            for action in ACTIONS {
+                top_level
+                    .spawn(NodeBundle {
+                        style: Style {
+                            // The buttons' margin spaces the rows.
+                            size: Size::width(Val::Percent(100.0)),
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn(
+                            TextBundle::from_section(
+                                action.name(),
+                                TextStyle {
+                                    font: font.clone(),
+                                    font_size: 16.0,
+                                    ..default()
+                                },
+                            )
+                            .with_style(Style {
+                                size: Size::width(Val::Percent(30.0)),
+                                ..default()
+                            }),
+                        );
+                        for slot in 0..KEY_SLOTS {
+                            spawn_control_button(
+                                row,
+                                ControlsButton::Rebind(action, slot),
+                                order,
+                                35.0,
+                                control_value(
+                                    ControlsButton::Rebind(action, slot),
+                                    &settings,
+                                    &listening,
+                                ),
+                                font.clone(),
+                            );
+                            order += 1;
+                        }
+                    });
+            }
+            for button in [ControlsButton::Reset, ControlsButton::Back] {
+                spawn_control_button(
+                    top_level,
+                    button,
+                    order,
+                    100.0,
+                    control_value(button, &settings, &listening),
+                    font.clone(),
+                );
+                order += 1;
+            }
+        });
+}
+
+fn spawn_control_button(
+    parent: &mut ChildBuilder,
+    button: ControlsButton,
+    order: usize,
+    width_percent: f32,
+    text: String,
+    font: Handle<Font>,
+) {
+    parent
+        .spawn((
+            button,
+            Focusable { order },
+            ButtonBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(width_percent), Val::Px(32.0)),
+                    margin: UiRect::bottom(Val::Px(4.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgb(0.15, 0.15, 1.00).into(),
+                ..default()
+            },
+        ))
+        .with_children(|button_node| {
+            button_node.spawn((
+                ControlValue(button),
+                TextBundle::from_section(
+                    text,
+                    TextStyle {
+                        font,
+                        font_size: 16.0,
+                        ..default()
+                    },
+                ),
+            ));
+        });
+}
+
+/// Binds the first key pressed while listening to the slot, see `KeyBindings::rebind` for what
+/// happens to other actions using that key.
+fn capture_rebind(
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    mut listening: ResMut<ListeningForKey>,
+    mut settings: ResMut<Settings>,
+) {
+    let Some((action, slot)) = listening.0 else { return };
+    let Some(key) = keyboard_input.get_just_pressed().next().copied() else { return };
+    settings.key_bindings.rebind(action, slot, key);
+    listening.0 = None;
+    // The key was only meant for the binding, menus shouldn't react to it.
+    keyboard_input.reset(key);
+}
+
+fn handle_controls(
+    interaction_query: Query<(&Interaction, &ControlsButton), Changed<Interaction>>,
+    action_input: Res<ActionInput>,
+    mut listening: ResMut<ListeningForKey>,
+    mut settings: ResMut<Settings>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    if listening.0.is_none() && action_input.just_pressed(Action::Cancel) {
+        state.set(GameState::Options);
+    }
+    for (interaction, button) in &interaction_query {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        match button {
+            ControlsButton::Rebind(action, slot) => listening.0 = Some((*action, *slot)),
+            ControlsButton::Reset => {
+                settings.key_bindings = KeyBindings::default();
+                listening.0 = None;
+            }
+            ControlsButton::Back => state.set(GameState::Options),
+        }
+    }
+}
+
+fn refresh_control_values(
+    settings: Res<Settings>,
+    listening: Res<ListeningForKey>,
+    mut value_query: Query<(&ControlValue, &mut Text)>,
+) {
+    if !settings.is_changed() && !listening.is_changed() {
+        return;
+    }
+    for (value, mut text) in &mut value_query {
+        text.sections[0].value = control_value(value.0, &settings, &listening);
+    }
+}
+
+fn remove_controls_screen(
+    mut commands: Commands,
+    mut listening: ResMut<ListeningForKey>,
+    screen_query: Query<Entity, With<ControlsScreen>>,
+) {
+    listening.0 = None;
+    let Some(screen_entity) = screen_query.iter().next() else { return };
+    commands.entity(screen_entity).despawn_recursive();
+}
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ListeningForKey>()
+            .add_system(add_controls_screen.in_schedule(OnEnter(GameState::Controls)))
+            .add_system(
+                capture_rebind
+                    .after(InputSystem)
+                    .before(update_action_input)
+                    .run_if(in_state(GameState::Controls))
+                    .in_base_set(CoreSet::PreUpdate),
+            )
+            .add_systems(
+                (
+                    handle_controls,
+                    refresh_control_values.after(handle_controls),
+                )
+                    .in_set(OnUpdate(GameState::Controls)),
+            )
+            .add_system(remove_controls_screen.in_schedule(OnExit(GameState::Controls)));
+    }
+}
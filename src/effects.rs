@@ -1,54 +1,176 @@
-use crate::utils::*;
+use crate::{settings::Settings, simulation::GameClock, utils::*};
 use bevy::prelude::*;
 use bevy::utils::HashMap;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageType {
+    Physical,
+    Fire,
+    Poison,
+    Explosion,
+}
+
+impl DamageType {
+    fn color(self) -> Color {
+        match self {
+            DamageType::Physical => Color::WHITE,
+            DamageType::Fire => Color::rgb(1.0, 0.55, 0.2),
+            DamageType::Poison => Color::rgb(0.5, 1.0, 0.3),
+            DamageType::Explosion => Color::rgb(1.0, 0.3, 0.3),
+        }
+    }
+
+    /// Damage over time ticks often, so those numbers are drawn smaller.
+    fn scale(self) -> f32 {
+        match self {
+            DamageType::Fire | DamageType::Poison => 0.8,
+            _ => 1.0,
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct DamageNumberEvent {
     pub dmg: i32,
     pub position: Vec3,
+    pub crit: bool,
+    pub damage_type: DamageType,
+    /// The entity that was hit.
+    pub target: Entity,
 }
 
 #[derive(Component)]
 pub struct DamageNumber {
     move_towards: Vec2,
+    scale: f32,
+    target: Entity,
+    damage_type: DamageType,
+    total: i32,
+    crit: bool,
 }
 
 #[derive(Resource, Deref, DerefMut)]
 pub struct ActiveDamageEffects(pub HashMap<Entity, f32>);
 
 const EFFECT_Z_LAYER: f32 = 99.9;
+const DAMAGE_NUMBER_LIFETIME: f32 = 0.4;
+const CRIT_SCALE: f32 = 1.6;
+// Numbers spawn this much bigger than their final size and shrink into place.
+const POP_SCALE: f32 = 1.5;
+// How much further an aggregated number rises with every hit added to it.
+const AGGREGATE_RISE: f32 = 4.0;
+
+fn damage_number_text(total: i32, crit: bool, damage_type: DamageType, font: Handle<Font>) -> Text {
+    let value = if crit {
+        format!("{}!", total)
+    } else {
+        total.to_string()
+    };
+    Text::from_section(
+        value,
+        TextStyle {
+            font,
+            color: damage_type.color(),
+            ..default()
+        },
+    )
+    .with_alignment(TextAlignment::Center)
+}
+
+fn damage_number_scale(crit: bool, damage_type: DamageType) -> f32 {
+    if crit {
+        damage_type.scale() * CRIT_SCALE
+    } else {
+        damage_type.scale()
+    }
+}
 
 pub fn display_damage_numbers(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
     mut damage_number_reader: EventReader<DamageNumberEvent>,
     mut active_damage_effects: ResMut<ActiveDamageEffects>,
+    mut damage_number_query: Query<(Entity, &mut DamageNumber, &mut Text, &mut Transform)>,
 ) {
+    if !settings.damage_numbers {
+        damage_number_reader.clear();
+        return;
+    }
+    let mut damage_number_events: Vec<DamageNumberEvent> = Vec::new();
     for damage_number_event in damage_number_reader.iter() {
-        let text_style = TextStyle {
-            font: asset_server.load("pixel_font.ttf"),
-            ..default()
-        };
+        let merged = damage_number_events.iter_mut().find(|merged| {
+            merged.target == damage_number_event.target
+                && merged.damage_type == damage_number_event.damage_type
+        });
+        match merged {
+            Some(merged) if settings.aggregate_damage_numbers => {
+                merged.dmg += damage_number_event.dmg;
+                merged.crit |= damage_number_event.crit;
+            }
+            _ => damage_number_events.push(damage_number_event.clone()),
+        }
+    }
+
+    for damage_number_event in damage_number_events {
+        let font = asset_server.load("pixel_font.ttf");
+        if settings.aggregate_damage_numbers {
+            let existing = damage_number_query
+                .iter_mut()
+                .find(|(_, damage_number, ..)| {
+                    damage_number.target == damage_number_event.target
+                        && damage_number.damage_type == damage_number_event.damage_type
+                });
+            if let Some((entity, mut damage_number, mut text, mut transform)) = existing {
+                damage_number.total += damage_number_event.dmg;
+                damage_number.crit |= damage_number_event.crit;
+                damage_number.scale =
+                    damage_number_scale(damage_number.crit, damage_number.damage_type);
+                damage_number.move_towards.y += AGGREGATE_RISE;
+                *text = damage_number_text(
+                    damage_number.total,
+                    damage_number.crit,
+                    damage_number.damage_type,
+                    font,
+                );
+                transform.scale = Vec3::splat(damage_number.scale * POP_SCALE);
+                active_damage_effects.insert(entity, DAMAGE_NUMBER_LIFETIME);
+                continue;
+            }
+        }
+
         let position = Vec2::new(
             damage_number_event.position.x,
             damage_number_event.position.y,
         );
+        let scale = damage_number_scale(damage_number_event.crit, damage_number_event.damage_type);
         let damage_number_entity = commands
             .spawn((
                 DamageNumber {
                     move_towards: Vec2::new(position.x + 2.0, position.y + 20.0),
+                    scale,
+                    target: damage_number_event.target,
+                    damage_type: damage_number_event.damage_type,
+                    total: damage_number_event.dmg,
+                    crit: damage_number_event.crit,
                 },
                 Text2dBundle {
-                    text: Text::from_section(
-                        damage_number_event.dmg.to_string(),
-                        text_style.clone(),
-                    )
-                    .with_alignment(TextAlignment::Center),
-                    transform: Transform::from_translation(position.extend(EFFECT_Z_LAYER)),
+                    text: damage_number_text(
+                        damage_number_event.dmg,
+                        damage_number_event.crit,
+                        damage_number_event.damage_type,
+                        font,
+                    ),
+                    transform: Transform {
+                        translation: position.extend(EFFECT_Z_LAYER),
+                        scale: Vec3::splat(scale * POP_SCALE),
+                        ..default()
+                    },
                     ..default()
                 },
             ))
             .id();
-        active_damage_effects.insert(damage_number_entity, 0.4);
+        active_damage_effects.insert(damage_number_entity, DAMAGE_NUMBER_LIFETIME);
     }
 }
 
@@ -56,11 +178,13 @@ pub fn animate_damage_numbers(mut transform_query: Query<(&mut Transform, &Damag
     for (mut transform, damage_number) in transform_query.iter_mut() {
         transform.translation.x = lerp(transform.translation.x, damage_number.move_towards.x, 0.3);
         transform.translation.y = lerp(transform.translation.y, damage_number.move_towards.y, 0.3);
+        let scale = lerp(transform.scale.x, damage_number.scale, 0.3);
+        transform.scale = Vec3::new(scale, scale, 1.0);
     }
 }
 
 pub fn remove_damage_numbers(
-    time: Res<Time>,
+    time: Res<GameClock>,
     mut commands: Commands,
     mut active_damage_efects: ResMut<ActiveDamageEffects>,
 ) {
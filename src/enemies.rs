@@ -0,0 +1,292 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    enemy_movement::{EnemyMovement, Movement},
+    physics_groups,
+    pickups::{PickupDrop, PickupKind},
+    status_effects::{StatusEffect, StatusEffects, StatusKind},
+    LoopAnimationTimer,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum EnemyKind {
+    Soyjak,
+    Cobson,
+    Bat,
+    Spitter,
+}
+
+pub const ENEMY_KINDS: [EnemyKind; 4] = [
+    EnemyKind::Soyjak,
+    EnemyKind::Cobson,
+    EnemyKind::Bat,
+    EnemyKind::Spitter,
+];
+
+pub struct EnemyDefinition {
+    pub hp: i32,
+    pub speed: f32,
+    pub damage: i32,
+    /// Half extents of the collider.
+    pub collider_size: Vec2,
+    pub spritesheet: &'static str,
+    pub tile_size: Vec2,
+    /// Frames are laid out in a single row, enemies with one frame aren't animated.
+    pub frames: usize,
+    pub frame_time: f32,
+    /// Tint applied to the sprite, lets archetypes share a spritesheet.
+    pub color: Color,
+    pub exp: i32,
+    pub movement: Movement,
+    pub drops: &'static [PickupDrop],
+}
+
+const COMMON_DROPS: [PickupDrop; 2] = [
+    PickupDrop {
+        kind: PickupKind::Gold,
+        chance: 0.02,
+    },
+    PickupDrop {
+        kind: PickupKind::Heal,
+        chance: 0.005,
+    },
+];
+
+const TOUGH_DROPS: [PickupDrop; 5] = [
+    PickupDrop {
+        kind: PickupKind::Gold,
+        chance: 0.06,
+    },
+    PickupDrop {
+        kind: PickupKind::Heal,
+        chance: 0.02,
+    },
+    PickupDrop {
+        kind: PickupKind::Vacuum,
+        chance: 0.005,
+    },
+    PickupDrop {
+        kind: PickupKind::Bomb,
+        chance: 0.005,
+    },
+    PickupDrop {
+        kind: PickupKind::Freeze,
+        chance: 0.005,
+    },
+];
+
+const SOYJAK: EnemyDefinition = EnemyDefinition {
+    hp: 10,
+    speed: 80.0,
+    damage: 5,
+    collider_size: Vec2::new(8.0, 8.0),
+    spritesheet: "soyjak.png",
+    tile_size: Vec2::new(28.0, 48.0),
+    frames: 1,
+    frame_time: 0.0,
+    color: Color::WHITE,
+    exp: 40,
+    movement: Movement::Chase,
+    drops: &COMMON_DROPS,
+};
+
+const COBSON: EnemyDefinition = EnemyDefinition {
+    hp: 40,
+    speed: 55.0,
+    damage: 10,
+    collider_size: Vec2::new(10.0, 12.0),
+    spritesheet: "cobson.png",
+    tile_size: Vec2::new(28.0, 52.0),
+    frames: 1,
+    frame_time: 0.0,
+    color: Color::WHITE,
+    exp: 100,
+    movement: Movement::Dash {
+        range: 120.0,
+        telegraph: 0.6,
+        dash_speed: 260.0,
+        dash_time: 0.5,
+        recovery: 0.8,
+    },
+    drops: &TOUGH_DROPS,
+};
+
+const BAT: EnemyDefinition = EnemyDefinition {
+    hp: 6,
+    speed: 120.0,
+    damage: 3,
+    collider_size: Vec2::new(6.0, 6.0),
+    spritesheet: "bat-sheet.png",
+    tile_size: Vec2::new(24.0, 24.0),
+    frames: 4,
+    frame_time: 0.1,
+    color: Color::WHITE,
+    exp: 25,
+    movement: Movement::Orbit { radius: 60.0 },
+    drops: &COMMON_DROPS,
+};
+
+const SPITTER: EnemyDefinition = EnemyDefinition {
+    hp: 8,
+    speed: 60.0,
+    damage: 3,
+    collider_size: Vec2::new(8.0, 8.0),
+    spritesheet: "soyjak.png",
+    tile_size: Vec2::new(28.0, 48.0),
+    frames: 1,
+    frame_time: 0.0,
+    color: Color::rgb(0.6, 1.0, 0.5),
+    exp: 60,
+    movement: Movement::Ranged {
+        distance: 160.0,
+        cooldown: 2.5,
+        projectile_speed: 150.0,
+        projectile_damage: 6,
+        projectile_effect: Some(StatusEffect {
+            kind: StatusKind::Poison,
+            strength: 1.0,
+            duration: 4.0,
+        }),
+    },
+    drops: &TOUGH_DROPS,
+};
+
+impl EnemyKind {
+    pub fn definition(self) -> &'static EnemyDefinition {
+        match self {
+            EnemyKind::Soyjak => &SOYJAK,
+            EnemyKind::Cobson => &COBSON,
+            EnemyKind::Bat => &BAT,
+            EnemyKind::Spitter => &SPITTER,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Enemy {
+    pub hp: i32,
+    pub speed: f32,
+    pub damage: i32,
+    pub exp: i32,
+    pub drops: &'static [PickupDrop],
+}
+
+impl Enemy {
+    pub fn new(kind: EnemyKind) -> Self {
+        let definition = kind.definition();
+        Self {
+            hp: definition.hp,
+            speed: definition.speed,
+            damage: definition.damage,
+            exp: definition.exp,
+            drops: definition.drops,
+        }
+    }
+}
+
+/// Tougher, bigger enemies that drop a chest when killed.
+#[derive(Component)]
+pub struct Elite {
+    pub scale: f32,
+}
+
+const ELITE_SCALE: f32 = 1.5;
+const ELITE_HP_MULTIPLIER: i32 = 8;
+
+#[derive(Resource, Default)]
+pub struct EnemyAtlases(HashMap<EnemyKind, Handle<TextureAtlas>>);
+
+fn load_enemy_atlases(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+) {
+    let mut enemy_atlases = EnemyAtlases::default();
+    for kind in ENEMY_KINDS {
+        let definition = kind.definition();
+        let texture_atlas = TextureAtlas::from_grid(
+            asset_server.load(definition.spritesheet),
+            definition.tile_size,
+            definition.frames,
+            1,
+            None,
+            None,
+        );
+        enemy_atlases
+            .0
+            .insert(kind, texture_atlases.add(texture_atlas));
+    }
+    commands.insert_resource(enemy_atlases);
+}
+
+pub fn spawn_enemy(
+    commands: &mut Commands,
+    enemy_atlases: &EnemyAtlases,
+    kind: EnemyKind,
+    translation: Vec3,
+) -> Entity {
+    let definition = kind.definition();
+    let mut enemy = commands.spawn((
+        Enemy::new(kind),
+        EnemyMovement::new(definition),
+        StatusEffects::default(),
+        SpriteSheetBundle {
+            sprite: TextureAtlasSprite {
+                color: definition.color,
+                ..default()
+            },
+            texture_atlas: enemy_atlases.0[&kind].clone(),
+            transform: Transform {
+                translation,
+                ..default()
+            },
+            ..default()
+        },
+        RigidBody::Dynamic,
+        Collider::cuboid(definition.collider_size.x, definition.collider_size.y),
+        CollisionGroups::new(
+            physics_groups::ENEMY_GROUP,
+            physics_groups::ENEMY_GROUP
+                | physics_groups::PLAYER_GROUP
+                | physics_groups::ATTACK_GROUP,
+        ),
+        LockedAxes::ROTATION_LOCKED,
+        Velocity::default(),
+    ));
+    if definition.frames > 1 {
+        enemy.insert(LoopAnimationTimer(Timer::from_seconds(
+            definition.frame_time,
+            TimerMode::Repeating,
+        )));
+    }
+    enemy.id()
+}
+
+pub fn make_elite(commands: &mut Commands, enemy_entity: Entity, kind: EnemyKind) {
+    let enemy = Enemy::new(kind);
+    commands.entity(enemy_entity).insert((
+        Elite { scale: ELITE_SCALE },
+        Enemy {
+            hp: enemy.hp * ELITE_HP_MULTIPLIER,
+            ..enemy
+        },
+    ));
+}
+
+fn grow_elites(mut elite_query: Query<(&Elite, &mut Transform), Added<Elite>>) {
+    for (elite, mut transform) in &mut elite_query {
+        transform.scale = Vec3::new(elite.scale, elite.scale, 1.0);
+    }
+}
+
+pub struct EnemiesPlugin;
+
+impl Plugin for EnemiesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(load_enemy_atlases)
+            .add_system(grow_elites);
+    }
+}
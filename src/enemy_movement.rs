@@ -0,0 +1,318 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    enemies::{Enemy, EnemyDefinition},
+    physics_groups,
+    player_damage::{PlayerDamageSet, PlayerHitEvent},
+    simulation::{GameClock, SimulationSet},
+    status_effects::{StatusEffect, StatusEffects},
+    Player,
+};
+
+// Enemies closer than this push each other apart so crowds don't collapse into one clump.
+const SEPARATION_RADIUS: f32 = 20.0;
+const SEPARATION_STRENGTH: f32 = 0.8;
+const TELEGRAPH_COLOR: Color = Color::rgb(1.0, 0.35, 0.35);
+
+#[derive(Clone, Copy)]
+pub enum Movement {
+    /// Walks straight at the player.
+    Chase,
+    /// Walks towards the player, stops to telegraph once in range and then charges.
+    Dash {
+        range: f32,
+        telegraph: f32,
+        dash_speed: f32,
+        dash_time: f32,
+        recovery: f32,
+    },
+    /// Circles the player while slowly closing in.
+    Orbit { radius: f32 },
+    /// Keeps its distance and shoots at the player.
+    Ranged {
+        distance: f32,
+        cooldown: f32,
+        projectile_speed: f32,
+        projectile_damage: i32,
+        projectile_effect: Option<StatusEffect>,
+    },
+}
+
+enum DashState {
+    Approaching,
+    Telegraphing,
+    Dashing(Vec2),
+    Recovering,
+}
+
+#[derive(Component)]
+pub struct EnemyMovement {
+    movement: Movement,
+    dash_state: DashState,
+    timer: Timer,
+    // Restored once the dash telegraph or a status tint is over.
+    color: Color,
+}
+
+impl EnemyMovement {
+    pub fn new(definition: &EnemyDefinition) -> Self {
+        let movement = definition.movement;
+        let timer = match movement {
+            Movement::Ranged { cooldown, .. } => {
+                Timer::from_seconds(cooldown, TimerMode::Repeating)
+            }
+            _ => Timer::default(),
+        };
+        Self {
+            movement,
+            dash_state: DashState::Approaching,
+            timer,
+            color: definition.color,
+        }
+    }
+
+    /// The sprite tint the enemy goes back to after being recolored.
+    pub fn color(&self) -> Color {
+        self.color
+    }
+}
+
+#[derive(Component)]
+pub struct EnemyProjectile {
+    damage: i32,
+    effect: Option<StatusEffect>,
+    lifetime: Timer,
+}
+
+fn separation_grid(
+    positions: impl Iterator<Item = (Entity, Vec2)>,
+) -> HashMap<IVec2, Vec<(Entity, Vec2)>> {
+    let mut grid: HashMap<IVec2, Vec<(Entity, Vec2)>> = HashMap::default();
+    for (entity, position) in positions {
+        let cell = (position / SEPARATION_RADIUS).floor().as_ivec2();
+        grid.entry(cell).or_default().push((entity, position));
+    }
+    grid
+}
+
+fn separation(grid: &HashMap<IVec2, Vec<(Entity, Vec2)>>, entity: Entity, position: Vec2) -> Vec2 {
+    let cell = (position / SEPARATION_RADIUS).floor().as_ivec2();
+    let mut push = Vec2::ZERO;
+    for x in -1..=1 {
+        for y in -1..=1 {
+            let Some(neighbours) = grid.get(&(cell + IVec2::new(x, y))) else { continue };
+            for (other_entity, other_position) in neighbours {
+                let away = position - *other_position;
+                let distance = away.length();
+                if *other_entity == entity || distance >= SEPARATION_RADIUS || distance == 0.0 {
+                    continue;
+                }
+                push += away / distance * (1.0 - distance / SEPARATION_RADIUS);
+            }
+        }
+    }
+    push
+}
+
+pub fn spawn_enemy_projectile(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    position: Vec3,
+    velocity: Vec2,
+    damage: i32,
+    effect: Option<StatusEffect>,
+) {
+    commands.spawn((
+        EnemyProjectile {
+            damage,
+            effect,
+            lifetime: Timer::from_seconds(5.0, TimerMode::Once),
+        },
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgb(0.4, 1.0, 0.3),
+                ..default()
+            },
+            texture: asset_server.load("effects/fireball.png"),
+            transform: Transform {
+                translation: position,
+                scale: Vec3::splat(0.5),
+                ..default()
+            },
+            ..default()
+        },
+        RigidBody::Dynamic,
+        Sensor,
+        Collider::ball(10.0),
+        CollisionGroups::new(
+            physics_groups::ENEMY_ATTACK_GROUP,
+            physics_groups::PLAYER_GROUP,
+        ),
+        Velocity::linear(velocity),
+    ));
+}
+
+pub fn move_enemies(
+    mut commands: Commands,
+    time: Res<GameClock>,
+    asset_server: Res<AssetServer>,
+    player_transform_query: Query<&Transform, With<Player>>,
+    mut enemy_query: Query<(
+        Entity,
+        &Enemy,
+        &mut EnemyMovement,
+        &Transform,
+        &mut Velocity,
+        &mut TextureAtlasSprite,
+        &StatusEffects,
+    )>,
+) {
+    let Some(player_transform) = player_transform_query.iter().next() else { return };
+    let player_position = player_transform.translation.truncate();
+    let grid = separation_grid(
+        enemy_query
+            .iter()
+            .map(|(entity, _, _, transform, ..)| (entity, transform.translation.truncate())),
+    );
+
+    for (entity, enemy, mut enemy_movement, transform, mut velocity, mut sprite, effects) in
+        enemy_query.iter_mut()
+    {
+        if let Some(knockback) = effects.knockback_velocity() {
+            velocity.linvel = knockback;
+            continue;
+        }
+        // Frozen enemies also stop their dash and shooting timers.
+        if effects.is_frozen() {
+            velocity.linvel = Vec2::ZERO;
+            continue;
+        }
+        let speed = enemy.speed * effects.speed_multiplier();
+        let position = transform.translation.truncate();
+        let to_player = player_position - position;
+        let distance = to_player.length();
+        let direction_to_player = to_player.try_normalize().unwrap_or(Vec2::ZERO);
+        enemy_movement.timer.tick(time.delta());
+
+        let desired = match enemy_movement.movement {
+            Movement::Chase => direction_to_player,
+            Movement::Dash {
+                range,
+                telegraph,
+                dash_speed,
+                dash_time,
+                recovery,
+            } => match enemy_movement.dash_state {
+                DashState::Approaching => {
+                    if distance < range {
+                        enemy_movement.dash_state = DashState::Telegraphing;
+                        enemy_movement.timer = Timer::from_seconds(telegraph, TimerMode::Once);
+                        sprite.color = TELEGRAPH_COLOR;
+                    }
+                    direction_to_player
+                }
+                DashState::Telegraphing => {
+                    // Kept up every frame so a status tint can't cover the warning.
+                    sprite.color = TELEGRAPH_COLOR;
+                    if enemy_movement.timer.finished() {
+                        enemy_movement.dash_state = DashState::Dashing(direction_to_player);
+                        enemy_movement.timer = Timer::from_seconds(dash_time, TimerMode::Once);
+                        sprite.color = enemy_movement.color;
+                    }
+                    Vec2::ZERO
+                }
+                DashState::Dashing(direction) => {
+                    if enemy_movement.timer.finished() {
+                        enemy_movement.dash_state = DashState::Recovering;
+                        enemy_movement.timer = Timer::from_seconds(recovery, TimerMode::Once);
+                    }
+                    // Dashes skip the separation so the charge keeps its line.
+                    velocity.linvel = direction * dash_speed * effects.speed_multiplier();
+                    sprite.flip_x = direction.x < 0.0;
+                    continue;
+                }
+                DashState::Recovering => {
+                    if enemy_movement.timer.finished() {
+                        enemy_movement.dash_state = DashState::Approaching;
+                    }
+                    direction_to_player * 0.5
+                }
+            },
+            Movement::Orbit { radius } => {
+                let radial = ((distance - radius) / radius).clamp(-1.0, 1.0) + 0.5;
+                (direction_to_player.perp() + direction_to_player * radial).normalize_or_zero()
+            }
+            Movement::Ranged {
+                distance: preferred_distance,
+                projectile_speed,
+                projectile_damage,
+                projectile_effect,
+                ..
+            } => {
+                if enemy_movement.timer.just_finished() {
+                    spawn_enemy_projectile(
+                        &mut commands,
+                        &asset_server,
+                        transform.translation,
+                        direction_to_player * projectile_speed,
+                        projectile_damage,
+                        projectile_effect,
+                    );
+                }
+                if distance > preferred_distance * 1.1 {
+                    direction_to_player
+                } else if distance < preferred_distance * 0.9 {
+                    -direction_to_player
+                } else {
+                    direction_to_player.perp() * 0.5
+                }
+            }
+        };
+
+        let push = separation(&grid, entity, position) * SEPARATION_STRENGTH;
+        velocity.linvel = (desired + push) * speed;
+        sprite.flip_x = direction_to_player.x < 0.0;
+    }
+}
+
+fn enemy_projectile_player_collisions(
+    mut commands: Commands,
+    time: Res<GameClock>,
+    rapier_context: Res<RapierContext>,
+    player_query: Query<Entity, With<Player>>,
+    mut projectile_query: Query<(Entity, &mut EnemyProjectile)>,
+    mut hit_writer: EventWriter<PlayerHitEvent>,
+) {
+    let Some(player_entity) = player_query.iter().next() else { return };
+
+    for (projectile_entity, mut projectile) in projectile_query.iter_mut() {
+        projectile.lifetime.tick(time.delta());
+        if projectile.lifetime.finished() {
+            commands.entity(projectile_entity).despawn();
+        } else if rapier_context.intersection_pair(player_entity, projectile_entity) == Some(true) {
+            hit_writer.send(PlayerHitEvent {
+                damage: projectile.damage,
+                source: Some(projectile_entity),
+                effect: projectile.effect,
+                damage_over_time: false,
+            });
+            commands.entity(projectile_entity).despawn();
+        }
+    }
+}
+
+pub struct EnemyMovementPlugin;
+
+impl Plugin for EnemyMovementPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (
+                move_enemies,
+                enemy_projectile_player_collisions.before(PlayerDamageSet::Receive),
+            )
+                .in_set(SimulationSet),
+        );
+    }
+}
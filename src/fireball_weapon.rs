@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+
+use crate::{
+    effects::DamageType,
+    passive_items::PassiveKind,
+    status_effects::{StatusEffect, StatusKind},
+    weapon::{
+        fire_projectiles, AddWeapon, Evolution, ProjectileTemplate, Targeting, WeaponBehaviour,
+        WeaponDefinition, WeaponKind, WeaponLevel,
+    },
+};
+
+pub struct FireballWeaponPlugin;
+
+impl Plugin for FireballWeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_weapon(WeaponDefinition {
+            kind: WeaponKind::Fireball,
+            name: "Fireball",
+            description: "Launches a fireball at the nearest enemy.",
+            icon: "effects/fireball.png",
+            icon_color: Color::WHITE,
+            weight: 4.0,
+            targeting: Targeting::NearestEnemy,
+            projectile: ProjectileTemplate {
+                texture: Some("effects/fireball.png"),
+                color: Color::WHITE,
+                scale: 1.0,
+                collider_radius: 10.0,
+                spread: 0.2,
+                lifetime: Some(3.0),
+                max_range: Some(400.0),
+                effect: Some(StatusEffect {
+                    kind: StatusKind::Burn,
+                    strength: 2.0,
+                    duration: 2.0,
+                }),
+                knockback: 0.0,
+                damage_type: DamageType::Fire,
+                crit_chance: 0.05,
+                crit_multiplier: 2.0,
+            },
+            levels: vec![
+                WeaponLevel {
+                    base_dmg: 9,
+                    extra_dmg: 3,
+                    cooldown: 0.5,
+                    amount: 1,
+                    speed: 200.0,
+                    pierce: 0,
+                    description: "Launches a fireball at the nearest enemy.",
+                },
+                WeaponLevel {
+                    base_dmg: 12,
+                    extra_dmg: 3,
+                    cooldown: 0.5,
+                    amount: 1,
+                    speed: 200.0,
+                    pierce: 0,
+                    description: "Fireballs deal 3 more damage.",
+                },
+                WeaponLevel {
+                    base_dmg: 12,
+                    extra_dmg: 3,
+                    cooldown: 0.45,
+                    amount: 2,
+                    speed: 200.0,
+                    pierce: 0,
+                    description: "Launches one more fireball.",
+                },
+                WeaponLevel {
+                    base_dmg: 15,
+                    extra_dmg: 3,
+                    cooldown: 0.4,
+                    amount: 2,
+                    speed: 240.0,
+                    pierce: 0,
+                    description: "Fireballs deal 3 more damage and fly faster.",
+                },
+                WeaponLevel {
+                    base_dmg: 15,
+                    extra_dmg: 3,
+                    cooldown: 0.35,
+                    amount: 3,
+                    speed: 240.0,
+                    pierce: 0,
+                    description: "Launches one more fireball.",
+                },
+                WeaponLevel {
+                    base_dmg: 20,
+                    extra_dmg: 5,
+                    cooldown: 0.3,
+                    amount: 3,
+                    speed: 260.0,
+                    pierce: 1,
+                    description: "Fireballs deal 5 more damage and pass through an enemy.",
+                },
+            ],
+            behaviour: WeaponBehaviour::Cooldown(fire_projectiles),
+            evolution: Some(Evolution {
+                passive: PassiveKind::Spinach,
+                into: WeaponKind::Hellfire,
+            }),
+        })
+        .add_weapon(WeaponDefinition {
+            kind: WeaponKind::Hellfire,
+            name: "Hellfire",
+            description: "Huge fireballs burst out in every direction and burn through enemies.",
+            icon: "effects/fireball.png",
+            icon_color: Color::rgb(1.0, 0.4, 0.1),
+            weight: 0.0,
+            targeting: Targeting::Around,
+            projectile: ProjectileTemplate {
+                texture: Some("effects/fireball.png"),
+                color: Color::rgb(1.0, 0.4, 0.1),
+                scale: 2.5,
+                collider_radius: 25.0,
+                spread: 0.0,
+                lifetime: Some(4.0),
+                max_range: Some(500.0),
+                effect: Some(StatusEffect {
+                    kind: StatusKind::Burn,
+                    strength: 8.0,
+                    duration: 3.0,
+                }),
+                knockback: 200.0,
+                damage_type: DamageType::Fire,
+                crit_chance: 0.1,
+                crit_multiplier: 2.0,
+            },
+            levels: vec![WeaponLevel {
+                base_dmg: 30,
+                extra_dmg: 10,
+                cooldown: 1.2,
+                amount: 6,
+                speed: 150.0,
+                pierce: 10,
+                description:
+                    "Huge fireballs burst out in every direction and burn through enemies.",
+            }],
+            behaviour: WeaponBehaviour::Cooldown(fire_projectiles),
+            evolution: None,
+        });
+    }
+}
@@ -0,0 +1,271 @@
+use bevy::prelude::*;
+
+use crate::{
+    boss::BossHpBar,
+    camera::ScreenShake,
+    cat_weapon::CatOrbitTime,
+    chest::{Chest, UnopenedChests},
+    effects::{ActiveDamageEffects, DamageNumber},
+    enemies::Enemy,
+    enemy_movement::EnemyProjectile,
+    gems::Gem,
+    hud::InventoryHud,
+    menu_navigation::Focusable,
+    passive_items::ShieldState,
+    pickups::Pickup,
+    player_damage::PlayerDamagedEvent,
+    props::Prop,
+    simulation::{GameClock, SimulationSet},
+    spawn_director::EnemySpawner,
+    stats::Regenerated,
+    upgrades::UpgradePool,
+    weapon::Attack,
+    GameState, Player,
+};
+
+/// Everything the results screen shows about the current run.
+#[derive(Resource, Default)]
+pub struct RunStats {
+    pub time_survived: f32,
+    pub kills: u32,
+    pub damage_dealt: i32,
+    pub gold: u32,
+    pub damage_taken: i32,
+}
+
+#[derive(Component)]
+struct GameOverScreen;
+
+#[derive(Component, Clone, Copy)]
+enum GameOverButton {
+    Restart,
+    MainMenu,
+}
+
+fn track_time_survived(time: Res<GameClock>, mut run_stats: ResMut<RunStats>) {
+    run_stats.time_survived += time.delta_seconds();
+}
+
+fn track_damage_taken(
+    mut damaged_reader: EventReader<PlayerDamagedEvent>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    for damaged in damaged_reader.iter() {
+        run_stats.damage_taken += damaged.damage;
+    }
+}
+
+fn detect_player_death(player_query: Query<&Player>, mut state: ResMut<NextState<GameState>>) {
+    let Some(player) = player_query.iter().next() else { return };
+    if player.hp <= 0 {
+        state.set(GameState::GameOver);
+    }
+}
+
+fn add_game_over_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    run_stats: Res<RunStats>,
+    player_query: Query<&Player>,
+) {
+    let level = player_query.iter().next().map_or(1, |player| player.lvl);
+    let seconds = run_stats.time_survived as u32;
+    let results = [
+        format!("Time survived: {:02}:{:02}", seconds / 60, seconds % 60),
+        format!("Level: {}", level),
+        format!("Kills: {}", run_stats.kills),
+        format!("Damage dealt: {}", run_stats.damage_dealt),
+        format!("Damage taken: {}", run_stats.damage_taken),
+        format!("Gold: {}", run_stats.gold),
+    ];
+
+    commands
+        .spawn((
+            GameOverScreen,
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    size: Size {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                    },
+                    padding: UiRect::all(Val::Px(40.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                ..default()
+            },
+        ))
+        .with_children(|top_level| {
+            top_level
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size {
+                            width: Val::Percent(100.0),
+                            height: Val::Px(40.0),
+                        },
+                        margin: UiRect {
+                            bottom: Val::Px(24.0),
+                            ..default()
+                        },
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.6, 0.1, 0.1).into(),
+                    ..default()
+                })
+                .with_children(|node| {
+                    node.spawn(TextBundle::from_section(
+                        "Game Over",
+                        TextStyle {
+                            font: asset_server.load("pixel_font.ttf"),
+                            ..default()
+                        },
+                    ));
+                });
+            for result in results {
+                top_level.spawn(
+                    TextBundle::from_section(
+                        result,
+                        TextStyle {
+                            font: asset_server.load("pixel_font.ttf"),
+                            font_size: 16.0,
+                            ..default()
+                        },
+                    )
+                    .with_style(Style {
+                        margin: UiRect::bottom(Val::Px(8.0)),
+                        ..default()
+                    }),
+                );
+            }
+            top_level
+                .spawn(NodeBundle {
+                    style: Style {
+                        margin: UiRect::top(Val::Px(24.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    for (order, (button, label)) in [
+                        (GameOverButton::Restart, "Restart"),
+                        (GameOverButton::MainMenu, "Main Menu"),
+                    ]
+                    .into_iter()
+                    .enumerate()
+                    {
+                        row.spawn((
+                            button,
+                            Focusable { order },
+                            ButtonBundle {
+                                style: Style {
+                                    size: Size::new(Val::Px(160.0), Val::Px(40.0)),
+                                    margin: UiRect::horizontal(Val::Px(8.0)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                background_color: Color::rgb(0.15, 0.15, 1.00).into(),
+                                ..default()
+                            },
+                        ))
+                        .with_children(|button| {
+                            button.spawn(TextBundle::from_section(
+                                label,
+                                TextStyle {
+                                    font: asset_server.load("pixel_font.ttf"),
+                                    ..default()
+                                },
+                            ));
+                        });
+                    }
+                });
+        });
+}
+
+/// Restart starts a new run with the same character, the run is cleaned up when leaving this screen.
+fn handle_game_over_buttons(
+    interaction_query: Query<(&Interaction, &GameOverButton), Changed<Interaction>>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        match button {
+            GameOverButton::Restart => state.set(GameState::Playing),
+            GameOverButton::MainMenu => state.set(GameState::MainMenu),
+        }
+    }
+}
+
+fn remove_game_over_screen(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<GameOverScreen>>,
+) {
+    let Some(screen_entity) = screen_query.iter().next() else { return };
+    commands.entity(screen_entity).despawn_recursive();
+}
+
+type RunEntityFilter = Or<(
+    With<Player>,
+    With<Enemy>,
+    With<EnemyProjectile>,
+    With<EnemySpawner>,
+    With<Attack>,
+    With<Gem>,
+    With<Chest>,
+    With<Pickup>,
+    With<Prop>,
+    With<BossHpBar>,
+    With<DamageNumber>,
+    With<InventoryHud>,
+)>;
+
+/// Despawns everything from the last finished or abandoned run so the next time `Playing` is entered
+/// starts a new one. Runs when leaving the game over screen and when quitting to the main menu.
+#[allow(clippy::too_many_arguments)]
+pub fn cleanup_run(
+    mut commands: Commands,
+    mut run_stats: ResMut<RunStats>,
+    mut upgrade_pool: ResMut<UpgradePool>,
+    mut active_damage_effects: ResMut<ActiveDamageEffects>,
+    mut unopened_chests: ResMut<UnopenedChests>,
+    mut shield_state: ResMut<ShieldState>,
+    mut regenerated: ResMut<Regenerated>,
+    mut cat_orbit_time: ResMut<CatOrbitTime>,
+    mut screen_shake: ResMut<ScreenShake>,
+    run_entity_query: Query<Entity, RunEntityFilter>,
+) {
+    for entity in run_entity_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    active_damage_effects.clear();
+    *run_stats = RunStats::default();
+    *upgrade_pool = UpgradePool::default();
+    *unopened_chests = UnopenedChests::default();
+    *shield_state = ShieldState::default();
+    *regenerated = Regenerated::default();
+    *cat_orbit_time = CatOrbitTime::default();
+    *screen_shake = ScreenShake::default();
+}
+
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .add_systems(
+                (track_time_survived, track_damage_taken, detect_player_death)
+                    .in_set(SimulationSet),
+            )
+            .add_system(add_game_over_screen.in_schedule(OnEnter(GameState::GameOver)))
+            .add_system(handle_game_over_buttons.in_set(OnUpdate(GameState::GameOver)))
+            .add_systems(
+                (remove_game_over_screen, cleanup_run).in_schedule(OnExit(GameState::GameOver)),
+            );
+    }
+}
@@ -0,0 +1,238 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    physics_groups,
+    simulation::SimulationSet,
+    stats::{PlayerStats, Stat},
+    Player,
+};
+
+// Past this many gems on the field, nearby gems get merged into one.
+const MAX_GEMS: usize = 200;
+const MERGE_CELL_SIZE: f32 = 64.0;
+const GEM_PULL_SPEED: f32 = 250.0;
+
+struct GemTier {
+    min_exp: i32,
+    color: Color,
+    scale: f32,
+}
+
+const GEM_TIERS: [GemTier; 4] = [
+    GemTier {
+        min_exp: 0,
+        color: Color::rgb(0.4, 0.6, 1.0),
+        scale: 0.5,
+    },
+    GemTier {
+        min_exp: 50,
+        color: Color::rgb(0.3, 1.0, 0.4),
+        scale: 0.6,
+    },
+    GemTier {
+        min_exp: 150,
+        color: Color::rgb(1.0, 0.3, 0.3),
+        scale: 0.75,
+    },
+    GemTier {
+        min_exp: 500,
+        color: Color::rgb(0.8, 0.3, 1.0),
+        scale: 0.9,
+    },
+];
+
+#[derive(Component)]
+pub struct Gem {
+    exp: i32,
+    /// Set once the gem enters the player's pickup radius, it keeps flying towards the player
+    /// until collected.
+    pub attracted: bool,
+}
+
+fn gem_tier(exp: i32) -> &'static GemTier {
+    GEM_TIERS
+        .iter()
+        .rev()
+        .find(|tier| exp >= tier.min_exp)
+        .unwrap_or(&GEM_TIERS[0])
+}
+
+pub fn spawn_gem(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    position: Vec3,
+    exp: i32,
+) {
+    let tier = gem_tier(exp);
+    commands.spawn((
+        Gem {
+            exp,
+            attracted: false,
+        },
+        SpriteBundle {
+            sprite: Sprite {
+                color: tier.color,
+                ..default()
+            },
+            texture: asset_server.load("gem.png"),
+            transform: Transform {
+                translation: position,
+                scale: Vec3::splat(tier.scale),
+                ..default()
+            },
+            ..default()
+        },
+        RigidBody::Dynamic,
+        Sensor,
+        Collider::ball(10.0),
+        CollisionGroups::new(physics_groups::PICKUP_GROUP, physics_groups::PLAYER_GROUP),
+        Velocity::default(),
+    ));
+}
+
+pub fn pickup_gems(
+    mut commands: Commands,
+    rapier_context: Res<RapierContext>,
+    mut player_query: Query<(Entity, &mut Player, &PlayerStats)>,
+    gem_query: Query<&Gem>,
+) {
+    let Some((player_entity, mut player, stats)) = player_query.iter_mut().next() else { return };
+    for (collider1, collider2, intersecting) in rapier_context.intersections_with(player_entity) {
+        if intersecting {
+            let gem_entity = if collider1 == player_entity {
+                collider2
+            } else {
+                collider1
+            };
+            let Ok(gem) = gem_query.get(gem_entity) else { continue };
+            commands.entity(gem_entity).despawn();
+            player.curr_exp += (gem.exp as f32 * stats.get(Stat::Growth)).round() as i32;
+        }
+    }
+}
+
+fn dress_gems(mut gem_query: Query<(&Gem, &mut Sprite, &mut Transform), Changed<Gem>>) {
+    for (gem, mut sprite, mut transform) in &mut gem_query {
+        let tier = gem_tier(gem.exp);
+        sprite.color = tier.color;
+        transform.scale = Vec3::splat(tier.scale);
+    }
+}
+
+fn attract_gems(
+    player_query: Query<(&PlayerStats, &Transform), With<Player>>,
+    mut gem_query: Query<(&mut Gem, &Transform, &mut Velocity)>,
+) {
+    let Some((stats, player_transform)) = player_query.iter().next() else { return };
+    let magnet = stats.get(Stat::Magnet);
+    let player_position = player_transform.translation.truncate();
+
+    for (mut gem, gem_transform, mut velocity) in &mut gem_query {
+        let to_player = player_position - gem_transform.translation.truncate();
+        if !gem.attracted {
+            if to_player.length() > magnet {
+                continue;
+            }
+            gem.attracted = true;
+        }
+        velocity.linvel = to_player.normalize_or_zero() * GEM_PULL_SPEED;
+    }
+}
+
+/// Groups gems that get merged into one, cells are widened until there are at most `MAX_GEMS`
+/// groups so the gem count stays bounded however spread out the gems are.
+fn merge_groups(positions: &[Vec2]) -> Vec<Vec<usize>> {
+    let mut cell_size = MERGE_CELL_SIZE;
+    loop {
+        let mut cells: HashMap<IVec2, Vec<usize>> = HashMap::default();
+        for (index, position) in positions.iter().enumerate() {
+            let cell = (*position / cell_size).floor().as_ivec2();
+            cells.entry(cell).or_default().push(index);
+        }
+        if cells.len() <= MAX_GEMS {
+            return cells.into_values().collect();
+        }
+        cell_size *= 2.0;
+    }
+}
+
+fn merge_gems(mut commands: Commands, mut gem_query: Query<(Entity, &mut Gem, &mut Transform)>) {
+    // Gems already flying to the player are left alone.
+    let resting_gems: Vec<(Entity, Vec2)> = gem_query
+        .iter()
+        .filter(|(_, gem, _)| !gem.attracted)
+        .map(|(gem_entity, _, transform)| (gem_entity, transform.translation.truncate()))
+        .collect();
+    if resting_gems.len() <= MAX_GEMS {
+        return;
+    }
+
+    let positions: Vec<Vec2> = resting_gems.iter().map(|(_, position)| *position).collect();
+    for group in merge_groups(&positions) {
+        // The first gem in the group absorbs the rest.
+        let Some((kept_index, merged_indices)) = group.split_first() else { continue };
+        if merged_indices.is_empty() {
+            continue;
+        }
+        let mut exp = 0;
+        let mut position = Vec2::ZERO;
+        for &index in &group {
+            let Ok((_, gem, _)) = gem_query.get(resting_gems[index].0) else { continue };
+            exp += gem.exp;
+            position += resting_gems[index].1;
+        }
+
+        let kept_entity = resting_gems[*kept_index].0;
+        let Ok((_, mut gem, mut transform)) = gem_query.get_mut(kept_entity) else { continue };
+        gem.exp = exp;
+        transform.translation = (position / group.len() as f32).extend(transform.translation.z);
+        for &index in merged_indices {
+            commands.entity(resting_gems[index].0).despawn();
+        }
+    }
+}
+
+pub struct GemsPlugin;
+
+impl Plugin for GemsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems((dress_gems, attract_gems, merge_gems).in_set(SimulationSet));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gem_tier_is_the_highest_tier_reached() {
+        assert_eq!(gem_tier(-5).min_exp, 0);
+        assert_eq!(gem_tier(0).min_exp, 0);
+        assert_eq!(gem_tier(49).min_exp, 0);
+        assert_eq!(gem_tier(50).min_exp, 50);
+        assert_eq!(gem_tier(499).min_exp, 150);
+        assert_eq!(gem_tier(10_000).min_exp, 500);
+    }
+
+    #[test]
+    fn merge_groups_leave_few_gems_apart() {
+        let positions = [Vec2::ZERO, Vec2::new(1000.0, 0.0), Vec2::new(0.0, 1000.0)];
+        assert_eq!(merge_groups(&positions).len(), 3);
+    }
+
+    #[test]
+    fn merge_groups_stay_under_the_cap_when_gems_are_spread_out() {
+        // One gem per cell, so merging within cells alone wouldn't remove any.
+        let positions: Vec<Vec2> = (0..MAX_GEMS * 3)
+            .map(|index| Vec2::new(index as f32 * MERGE_CELL_SIZE * 1.5, -(index as f32)))
+            .collect();
+        let groups = merge_groups(&positions);
+        assert!(groups.len() <= MAX_GEMS);
+
+        let mut indices: Vec<usize> = groups.into_iter().flatten().collect();
+        indices.sort();
+        assert_eq!(indices, (0..positions.len()).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+
+use crate::{
+    passive_items::{PassiveItem, MAX_PASSIVE_ITEMS},
+    run_not_started,
+    weapon::{Weapon, WeaponDefinitions, MAX_WEAPONS},
+    GameState,
+};
+
+const SLOT_SIZE: f32 = 22.0;
+const EMPTY_SLOT_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.5);
+
+/// Strip in the corner of the screen listing the weapons and passive items the player holds.
+#[derive(Component)]
+pub struct InventoryHud;
+
+struct HudSlot {
+    icon: &'static str,
+    icon_color: Color,
+    level: usize,
+}
+
+fn setup_inventory_hud(mut commands: Commands) {
+    commands.spawn((
+        InventoryHud,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(8.0),
+                    bottom: Val::Px(8.0),
+                    ..default()
+                },
+                flex_direction: FlexDirection::ColumnReverse,
+                ..default()
+            },
+            ..default()
+        },
+    ));
+}
+
+fn spawn_slot_row(
+    parent: &mut ChildBuilder,
+    asset_server: &AssetServer,
+    slots: Vec<HudSlot>,
+    slot_count: usize,
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                margin: UiRect::top(Val::Px(2.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|row| {
+            for index in 0..slot_count {
+                row.spawn(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(SLOT_SIZE), Val::Px(SLOT_SIZE)),
+                        margin: UiRect::right(Val::Px(2.0)),
+                        justify_content: JustifyContent::FlexEnd,
+                        align_items: AlignItems::FlexEnd,
+                        ..default()
+                    },
+                    background_color: EMPTY_SLOT_COLOR.into(),
+                    ..default()
+                })
+                .with_children(|slot_node| {
+                    let Some(slot) = slots.get(index) else { return };
+                    slot_node.spawn(ImageBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                            ..default()
+                        },
+                        image: asset_server.load(slot.icon).into(),
+                        background_color: slot.icon_color.into(),
+                        ..default()
+                    });
+                    slot_node.spawn(TextBundle::from_section(
+                        slot.level.to_string(),
+                        TextStyle {
+                            font: asset_server.load("pixel_font.ttf"),
+                            font_size: 10.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
+            }
+        });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn refresh_inventory_hud(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    definitions: Res<WeaponDefinitions>,
+    hud_query: Query<Entity, With<InventoryHud>>,
+    weapon_query: Query<Ref<Weapon>>,
+    passive_query: Query<Ref<PassiveItem>>,
+    mut removed_weapons: RemovedComponents<Weapon>,
+    mut removed_passives: RemovedComponents<PassiveItem>,
+) {
+    let Some(hud_entity) = hud_query.iter().next() else { return };
+    let changed = weapon_query.iter().any(|weapon| weapon.is_changed())
+        || passive_query.iter().any(|item| item.is_changed())
+        || removed_weapons.iter().count() > 0
+        || removed_passives.iter().count() > 0;
+    if !changed {
+        return;
+    }
+
+    let weapon_slots = weapon_query
+        .iter()
+        .map(|weapon| {
+            let definition = definitions.get(weapon.kind);
+            HudSlot {
+                icon: definition.icon,
+                icon_color: definition.icon_color,
+                level: weapon.level,
+            }
+        })
+        .collect();
+    let passive_slots = passive_query
+        .iter()
+        .map(|item| {
+            let definition = item.kind.definition();
+            HudSlot {
+                icon: definition.icon,
+                icon_color: definition.icon_color,
+                level: item.rank,
+            }
+        })
+        .collect();
+
+    let mut hud = commands.entity(hud_entity);
+    hud.despawn_descendants();
+    hud.with_children(|hud| {
+        spawn_slot_row(hud, &asset_server, passive_slots, MAX_PASSIVE_ITEMS);
+        spawn_slot_row(hud, &asset_server, weapon_slots, MAX_WEAPONS);
+    });
+}
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            setup_inventory_hud
+                .run_if(run_not_started)
+                .in_schedule(OnEnter(GameState::Playing)),
+        )
+        .add_system(refresh_inventory_hud);
+    }
+}
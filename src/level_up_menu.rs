@@ -1,17 +1,24 @@
 use bevy::prelude::*;
-use bevy_rapier2d::plugin::RapierConfiguration;
 
-use crate::{cat_weapon::AddCatWeaponEvent, GameState};
+use crate::{
+    menu_navigation::Focusable,
+    upgrades::{UpgradeChoices, UpgradeChosenEvent, UpgradeKind},
+    GameState,
+};
 
 #[derive(Component)]
 pub struct LevelUpMenu;
 
 #[derive(Component, Debug)]
 pub struct ItemChoice {
-    id: i32,
+    kind: UpgradeKind,
 }
 
-pub fn add_level_up_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+pub fn add_level_up_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    choices: Res<UpgradeChoices>,
+) {
     commands
         .spawn((
             LevelUpMenu,
@@ -75,88 +82,69 @@ pub fn add_level_up_menu(mut commands: Commands, asset_server: Res<AssetServer>)
                     ..default()
                 })
                 .with_children(|items_container| {
-                    items_container
-                        .spawn((
-                            ItemChoice { id: 1 },
-                            ButtonBundle {
-                                style: Style {
-                                    size: Size {
-                                        height: Val::Percent(100.0),
+                    for (index, upgrade) in choices.iter().enumerate() {
+                        items_container
+                            .spawn((
+                                ItemChoice { kind: upgrade.kind },
+                                Focusable { order: index },
+                                ButtonBundle {
+                                    style: Style {
+                                        flex_direction: FlexDirection::Column,
+                                        align_items: AlignItems::Center,
+                                        size: Size {
+                                            width: Val::Percent(100.0),
+                                            height: Val::Percent(100.0),
+                                        },
+                                        margin: UiRect {
+                                            left: if index == 0 {
+                                                Val::Px(0.0)
+                                            } else {
+                                                Val::Px(10.0)
+                                            },
+                                            ..default()
+                                        },
+                                        padding: UiRect::all(Val::Px(6.0)),
                                         ..default()
                                     },
-                                    flex_grow: 1.0,
-                                    ..default()
-                                },
-                                background_color: Color::rgb(0.15, 0.15, 1.00).into(),
-                                ..default()
-                            },
-                        ))
-                        .with_children(|item_choice| {
-                            item_choice.spawn(TextBundle::from_section(
-                                "Item Choice 1",
-                                TextStyle {
-                                    font: asset_server.load("pixel_font.ttf"),
+                                    background_color: Color::rgb(0.15, 0.15, 1.00).into(),
                                     ..default()
                                 },
-                            ));
-                        });
-                    items_container
-                        .spawn((
-                            ItemChoice { id: 2 },
-                            ButtonBundle {
-                                style: Style {
-                                    size: Size {
-                                        width: Val::Auto,
-                                        height: Val::Percent(100.0),
+                            ))
+                            .with_children(|item_choice| {
+                                item_choice.spawn(ImageBundle {
+                                    style: Style {
+                                        size: Size::new(Val::Px(48.0), Val::Px(48.0)),
+                                        margin: UiRect::all(Val::Px(8.0)),
                                         ..default()
                                     },
-                                    margin: UiRect {
-                                        left: Val::Px(10.0),
-                                        right: Val::Px(10.0),
-                                        ..default()
-                                    },
-                                    flex_grow: 1.0,
-                                    ..default()
-                                },
-                                background_color: Color::rgb(0.15, 0.15, 1.00).into(),
-                                ..default()
-                            },
-                        ))
-                        .with_children(|item_choice| {
-                            item_choice.spawn(TextBundle::from_section(
-                                "Item Choice 2",
-                                TextStyle {
-                                    font: asset_server.load("pixel_font.ttf"),
+                                    image: asset_server.load(upgrade.icon).into(),
+                                    background_color: upgrade.icon_color.into(),
                                     ..default()
-                                },
-                            ));
-                        });
-                    items_container
-                        .spawn((
-                            ItemChoice { id: 3 },
-                            ButtonBundle {
-                                style: Style {
-                                    size: Size {
-                                        width: Val::Auto,
-                                        height: Val::Percent(100.0),
+                                });
+                                item_choice.spawn(TextBundle::from_section(
+                                    upgrade.name.clone(),
+                                    TextStyle {
+                                        font: asset_server.load("pixel_font.ttf"),
+                                        font_size: 16.0,
                                         ..default()
                                     },
-                                    flex_grow: 1.0,
-                                    ..default()
-                                },
-                                background_color: Color::rgb(0.15, 0.15, 1.00).into(),
-                                ..default()
-                            },
-                        ))
-                        .with_children(|item_choice| {
-                            item_choice.spawn(TextBundle::from_section(
-                                "Item Choice 3",
-                                TextStyle {
-                                    font: asset_server.load("pixel_font.ttf"),
-                                    ..default()
-                                },
-                            ));
-                        });
+                                ));
+                                item_choice.spawn(
+                                    TextBundle::from_section(
+                                        upgrade.description.clone(),
+                                        TextStyle {
+                                            font: asset_server.load("pixel_font.ttf"),
+                                            ..default()
+                                        },
+                                    )
+                                    .with_style(Style {
+                                        max_size: Size::new(Val::Px(130.0), Val::Undefined),
+                                        margin: UiRect::top(Val::Px(8.0)),
+                                        ..default()
+                                    }),
+                                );
+                            });
+                    }
                 });
         });
 }
@@ -170,17 +158,14 @@ pub fn handle_choice(
     interaction_query: Query<(&Interaction, &ItemChoice), Changed<Interaction>>,
     mut keyboard_input: ResMut<Input<KeyCode>>,
     mut state: ResMut<NextState<GameState>>,
-    mut rapier_config: ResMut<RapierConfiguration>,
-    mut add_cat_weapon_event: EventWriter<AddCatWeaponEvent>,
+    mut upgrade_chosen_writer: EventWriter<UpgradeChosenEvent>,
 ) {
     for (interaction, item_choice) in &interaction_query {
         if let Interaction::Clicked = interaction {
-            dbg!(interaction);
-            dbg!(item_choice);
-            rapier_config.physics_pipeline_active = true;
             state.set(GameState::Playing);
             keyboard_input.reset(KeyCode::Space);
-            add_cat_weapon_event.send(AddCatWeaponEvent);
+            upgrade_chosen_writer.send(UpgradeChosenEvent(item_choice.kind));
+            return;
         }
     }
 }
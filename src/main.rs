@@ -1,29 +1,89 @@
+mod actions;
+mod background;
 mod bgm;
+mod boss;
 mod camera;
 mod cat_weapon;
+mod characters;
+mod chest;
+mod controls;
 mod effects;
+mod enemies;
+mod enemy_movement;
+mod fireball_weapon;
+mod game_over;
+mod gems;
+mod hud;
 mod level_up_menu;
+mod main_menu;
+mod menu_navigation;
+mod options;
+mod passive_items;
+mod pause_menu;
 mod physics_groups;
+mod pickups;
+mod player_damage;
+mod props;
+mod settings;
+mod sfx;
+mod simulation;
+mod spawn_director;
+mod stats;
+mod status_effects;
+mod upgrades;
 mod utils;
+mod weapon;
 
 use crate::utils::*;
+use actions::{ActionInput, ActionsPlugin};
+use background::BackgroundPlugin;
 use bevy::prelude::*;
 use bevy::sprite::*;
 use bevy::utils::HashMap;
 use bevy::window::WindowResolution;
 use bevy_rapier2d::prelude::*;
 use bgm::BgmPlugin;
+use boss::BossPlugin;
 use camera::CameraPlugin;
 use cat_weapon::CatWeaponPlugin;
-use rand::Rng;
-use std::f32::consts::PI;
+use characters::{CharactersPlugin, SelectedCharacter};
+use chest::ChestPlugin;
+use controls::ControlsPlugin;
+use enemies::{Elite, EnemiesPlugin, Enemy};
+use enemy_movement::EnemyMovementPlugin;
+use fireball_weapon::FireballWeaponPlugin;
+use game_over::{GameOverPlugin, RunStats};
+use gems::GemsPlugin;
+use hud::HudPlugin;
+use main_menu::MainMenuPlugin;
+use menu_navigation::MenuNavigationPlugin;
+use options::OptionsPlugin;
+use passive_items::PassiveItemsPlugin;
+use pause_menu::PauseMenuPlugin;
+use pickups::PickupsPlugin;
+use player_damage::{Invincibility, PlayerDamagePlugin, PlayerDamageSet, PlayerHitEvent};
+use props::PropsPlugin;
+use settings::SettingsPlugin;
+use sfx::SfxPlugin;
+use simulation::{GameClock, SimulationPlugin, SimulationSet};
+use spawn_director::SpawnDirectorPlugin;
+use stats::{PlayerStats, Stat, StatsPlugin};
+use status_effects::{StatusEffects, StatusEffectsPlugin};
+use upgrades::UpgradesPlugin;
+use weapon::{Attack, Weapon, WeaponPlugin};
 
 #[derive(States, Default, Debug, PartialEq, Eq, Hash, Copy, Clone)]
 pub enum GameState {
     #[default]
+    MainMenu,
+    Options,
+    Controls,
+    CharacterSelect,
     Playing,
     LevellingUp,
+    OpeningChest,
     Paused,
+    GameOver,
 }
 
 #[derive(Component, Deref, DerefMut)]
@@ -32,13 +92,6 @@ struct PlayerAnimationTimer(Timer);
 #[derive(Component, Deref, DerefMut)]
 struct LoopAnimationTimer(Timer);
 
-#[derive(Component)]
-struct FireballWeapon {
-    base_dmg: i32,
-    extra_dmg: i32,
-    spawn_timer: Timer,
-}
-
 #[derive(Component)]
 pub struct Player {
     lvl: i32,
@@ -54,66 +107,51 @@ struct PlayerHpBar;
 #[derive(Component)]
 struct PlayerExpBar;
 
-#[derive(Resource, Deref, DerefMut)]
-struct PlayerHitCooldown(HashMap<Entity, f32>);
-
-#[derive(Component)]
-struct EnemySpawner {
-    timer: Timer,
-}
-
-#[derive(Component)]
-struct Enemy {
-    hp: i32,
-}
-
-#[derive(Component)]
-struct Attack {
-    base_dmg: i32,
-    extra_dmg: i32,
-}
-
 const WINDOW_SIZE: f32 = 500.0;
-const PLAYER_SPEED: f32 = 100.0;
 const PLAYER_HP_WIDTH: f32 = 18.0;
 const PLAYER_EXP_WIDTH: f32 = 440.0;
-const PLAYER_SPRITE_DIMENSIONS: (f32, f32) = (28.0, 46.0);
-const FIREBALL_COOLDOWN: f32 = 0.5;
-const FIREBALL_SPEED: f32 = 200.0;
-const ENEMY_SPEED: f32 = 80.0;
-const ENEMY_DAMAGE: i32 = 5;
-const ENEMY_DAMAGE_COOLDOWN: f32 = 0.5;
-const GEM_EXP: i32 = 40;
 
 fn global_setup(mut commands: Commands) {
     commands.spawn(Camera2dBundle::default());
 }
 
+/// Entering `Playing` starts a new run unless coming back from a menu in the middle of one.
+pub fn run_not_started(player_query: Query<(), With<Player>>) -> bool {
+    player_query.is_empty()
+}
+
 fn setup_player(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    selected_character: Res<SelectedCharacter>,
 ) {
-    let spritesheet_handle = asset_server.load("chudjak.png");
+    let character = selected_character.definition();
+    let spritesheet_handle = asset_server.load(character.spritesheet);
     let texture_atlas = TextureAtlas::from_grid(
         spritesheet_handle,
-        Vec2::new(PLAYER_SPRITE_DIMENSIONS.0, PLAYER_SPRITE_DIMENSIONS.1),
-        7,
+        character.tile_size,
+        character.frames,
         1,
         None,
         None,
     );
+    let mut stats = PlayerStats::with_base_values(character.base_stats);
+    stats.add_modifier(character.bonus);
     commands
         .spawn((
             Player {
                 lvl: 1,
                 curr_exp: 0,
                 next_exp: 100,
-                max_hp: 100,
-                hp: 100,
+                max_hp: character.max_hp,
+                hp: character.max_hp,
             },
+            stats,
+            StatusEffects::default(),
+            Invincibility::default(),
             SpriteSheetBundle {
-                sprite: TextureAtlasSprite::new(1),
+                sprite: TextureAtlasSprite::new(character.idle_frame),
                 texture_atlas: texture_atlases.add(texture_atlas),
                 transform: Transform {
                     translation: Vec3::new(0.0, 0.0, 1.0),
@@ -122,16 +160,13 @@ fn setup_player(
                 ..default()
             },
             PlayerAnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
-            FireballWeapon {
-                base_dmg: 9,
-                extra_dmg: 3,
-                spawn_timer: Timer::from_seconds(FIREBALL_COOLDOWN, TimerMode::Repeating),
-            },
             RigidBody::Dynamic,
             Collider::cuboid(8.0, 10.0),
             CollisionGroups::new(
                 physics_groups::PLAYER_GROUP,
-                physics_groups::ENEMY_GROUP | physics_groups::PICKUP_GROUP,
+                physics_groups::ENEMY_GROUP
+                    | physics_groups::ENEMY_ATTACK_GROUP
+                    | physics_groups::PICKUP_GROUP,
             ),
             LockedAxes::ROTATION_LOCKED,
             // Make it so the player stays stationary when colliding with enemies.
@@ -140,6 +175,7 @@ fn setup_player(
             ActiveEvents::COLLISION_EVENTS,
         ))
         .with_children(|parent| {
+            parent.spawn(Weapon::new(character.starting_weapon));
             parent.spawn(SpriteBundle {
                 sprite: Sprite {
                     color: Color::rgb(1.0, 0.0, 0.0),
@@ -191,97 +227,8 @@ fn setup_player(
         });
 }
 
-fn setup_background(mut commands: Commands, asset_server: Res<AssetServer>) {
-    // TODO: This only covers a 3x3 grid, it needs to be endless.
-    let world_size = 3;
-    let background_size = 1024.0;
-    let starting_point = -(background_size * ((world_size - 1) as f32) / 2.0);
-    for row in 0..world_size {
-        for column in 0..world_size {
-            commands.spawn(SpriteBundle {
-                texture: asset_server.load("background.png"),
-                transform: Transform {
-                    translation: Vec3::new(
-                        remap(
-                            0.0,
-                            (world_size - 1) as f32,
-                            starting_point,
-                            -starting_point,
-                            row as f32,
-                        ),
-                        remap(
-                            0.0,
-                            (world_size - 1) as f32,
-                            starting_point,
-                            -starting_point,
-                            column as f32,
-                        ),
-                        0.0,
-                    ),
-                    ..default()
-                },
-                ..default()
-            });
-        }
-    }
-}
-
-fn setup_spawns(mut commands: Commands) {
-    commands.spawn(EnemySpawner {
-        timer: Timer::from_seconds(0.4, TimerMode::Repeating),
-    });
-}
-
-fn spawn_enemies(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    time: Res<Time>,
-    mut enemy_spawner_query: Query<&mut EnemySpawner>,
-    player_transform_query: Query<&Transform, With<Player>>,
-) {
-    let Some(player_transform) = player_transform_query.iter().next() else { return };
-    let mut rng = rand::thread_rng();
-    for mut enemy_spawner in &mut enemy_spawner_query {
-        enemy_spawner.timer.tick(time.delta());
-        if enemy_spawner.timer.just_finished() {
-            let distance_from_center: f32 = WINDOW_SIZE / 2.0;
-            let radius =
-                (distance_from_center.powf(2.0) + distance_from_center.powf(2.0)).sqrt() + 10.0;
-            let rotation = rng.gen_range(0.0..PI * 2.0);
-            let point_on_circle = Vec2::new(rotation.cos(), rotation.sin());
-            let point_around_player =
-                player_transform.translation + (point_on_circle * radius).extend(0.0);
-            spawn_soyjak(&mut commands, &asset_server, point_around_player);
-        }
-    }
-}
-
-fn spawn_soyjak(commands: &mut Commands, asset_server: &Res<AssetServer>, translation: Vec3) {
-    commands.spawn((
-        Enemy { hp: 10 },
-        SpriteBundle {
-            texture: asset_server.load("soyjak.png"),
-            transform: Transform {
-                translation,
-                ..default()
-            },
-            ..default()
-        },
-        RigidBody::Dynamic,
-        Collider::cuboid(8.0, 8.0),
-        CollisionGroups::new(
-            physics_groups::ENEMY_GROUP,
-            physics_groups::ENEMY_GROUP
-                | physics_groups::PLAYER_GROUP
-                | physics_groups::ATTACK_GROUP,
-        ),
-        LockedAxes::ROTATION_LOCKED,
-        Velocity::default(),
-    ));
-}
-
 fn animate_loops(
-    time: Res<Time>,
+    time: Res<GameClock>,
     texture_atlases: Res<Assets<TextureAtlas>>,
     mut query: Query<(
         &mut LoopAnimationTimer,
@@ -299,7 +246,7 @@ fn animate_loops(
 }
 
 fn animate_player(
-    time: Res<Time>,
+    time: Res<GameClock>,
     texture_atlases: Res<Assets<TextureAtlas>>,
     mut query: Query<(
         &mut PlayerAnimationTimer,
@@ -316,6 +263,10 @@ fn animate_player(
         timer.tick(time.delta());
         if timer.just_finished() {
             let texture_atlas = texture_atlases.get(texture_atlas_handle).unwrap();
+            // Characters with a single frame aren't animated.
+            if texture_atlas.textures.len() <= 1 {
+                continue;
+            }
             sprite.index += 1;
             if sprite.index >= texture_atlas.textures.len() {
                 sprite.index = 1;
@@ -326,58 +277,33 @@ fn animate_player(
 
 fn move_player(
     _time: Res<Time>,
-    keyboard_input: Res<Input<KeyCode>>,
-    mut query: Query<&mut Velocity, With<Player>>,
+    action_input: Res<ActionInput>,
+    mut query: Query<(&mut Velocity, &PlayerStats, &StatusEffects), With<Player>>,
 ) {
-    let Some(mut player_velocity) = query.iter_mut().next() else { return };
+    let Some((mut player_velocity, stats, effects)) = query.iter_mut().next() else { return };
+    let player_speed = stats.get(Stat::MoveSpeed) * effects.speed_multiplier();
+    let move_axis = action_input.move_axis();
 
     // TODO: Replace lerp with a spring https://theorangeduck.com/page/spring-roll-call
-    if keyboard_input.pressed(KeyCode::Left) {
-        player_velocity.linvel.x = lerp(player_velocity.linvel.x, -PLAYER_SPEED, 0.5);
-    } else if keyboard_input.pressed(KeyCode::Right) {
-        player_velocity.linvel.x = lerp(player_velocity.linvel.x, PLAYER_SPEED, 0.5);
+    if move_axis.x != 0.0 {
+        player_velocity.linvel.x = lerp(player_velocity.linvel.x, move_axis.x * player_speed, 0.5);
     } else {
         player_velocity.linvel.x = lerp(player_velocity.linvel.x, 0.0, 0.8);
     }
-    if keyboard_input.pressed(KeyCode::Down) {
-        player_velocity.linvel.y = lerp(player_velocity.linvel.y, -PLAYER_SPEED, 0.5);
-    } else if keyboard_input.pressed(KeyCode::Up) {
-        player_velocity.linvel.y = lerp(player_velocity.linvel.y, PLAYER_SPEED, 0.5);
+    if move_axis.y != 0.0 {
+        player_velocity.linvel.y = lerp(player_velocity.linvel.y, move_axis.y * player_speed, 0.5);
     } else {
         player_velocity.linvel.y = lerp(player_velocity.linvel.y, 0.0, 0.8);
     }
 }
 
-fn move_towards_player(
-    player_transform_query: Query<&Transform, With<Player>>,
-    mut enemy_query: Query<(&Transform, &mut Velocity, &mut Sprite), With<Enemy>>,
-) {
-    let Some(player_transform) = player_transform_query.iter().next() else { return };
-    for (enemy_transform, mut enemy_velocity, mut sprite) in enemy_query.iter_mut() {
-        let direction_to_player = (player_transform.translation - enemy_transform.translation)
-            .normalize()
-            .truncate();
-        enemy_velocity.linvel = direction_to_player * ENEMY_SPEED;
-        sprite.flip_x = direction_to_player.x < 0.0;
-    }
-}
-
 fn player_enemy_collisions(
-    time: Res<Time>,
     rapier_context: Res<RapierContext>,
-    mut player_hit_cooldown: ResMut<PlayerHitCooldown>,
-    mut player_entity_query: Query<(Entity, &mut Player)>,
-    enemy_query: Query<Entity, With<Enemy>>,
+    player_entity_query: Query<Entity, With<Player>>,
+    enemy_query: Query<&Enemy>,
+    mut hit_writer: EventWriter<PlayerHitEvent>,
 ) {
-    let (player_entity, mut player) = player_entity_query.single_mut();
-
-    let delta_seconds = time.delta_seconds();
-    player_hit_cooldown
-        .drain_filter(|_k, v| {
-            *v -= delta_seconds;
-            *v <= 0.0
-        })
-        .for_each(drop);
+    let player_entity = player_entity_query.single();
 
     for contact_pair in rapier_context.contacts_with(player_entity) {
         let enemy_collider = if contact_pair.collider1() == player_entity {
@@ -387,110 +313,126 @@ fn player_enemy_collisions(
         };
 
         // TODO: Could be done another way, maybe filter groups in rapier?
-        if enemy_query.get(enemy_collider).is_ok() {
-            if !player_hit_cooldown.contains_key(&enemy_collider) {
-                player.hp -= ENEMY_DAMAGE;
-                player_hit_cooldown.insert(enemy_collider, ENEMY_DAMAGE_COOLDOWN);
-            }
-        }
+        let Ok(enemy) = enemy_query.get(enemy_collider) else { continue };
+        hit_writer.send(PlayerHitEvent {
+            damage: enemy.damage,
+            source: Some(enemy_collider),
+            effect: None,
+            damage_over_time: false,
+        });
     }
 }
 
 fn attack_enemy_collisions(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
     rapier_context: Res<RapierContext>,
-    attack_query: Query<(Entity, &Attack)>,
-    mut enemy_query: Query<(&mut Enemy, &Transform)>,
+    mut attack_query: Query<(Entity, &mut Attack, &GlobalTransform)>,
+    mut enemy_query: Query<(&mut Enemy, &Transform, &mut StatusEffects)>,
     mut damage_number_writer: EventWriter<effects::DamageNumberEvent>,
+    mut run_stats: ResMut<RunStats>,
 ) {
     let mut rng = rand::thread_rng();
 
-    'attack_loop: for (attack_entity, attack) in attack_query.iter() {
+    for (attack_entity, mut attack, attack_transform) in attack_query.iter_mut() {
         for (collider1, collider2, intersecting) in rapier_context.intersections_with(attack_entity)
         {
-            if intersecting {
-                let enemy_entity = if collider1 == attack_entity {
-                    collider2
-                } else {
-                    collider1
-                };
-                let Ok((mut enemy, enemy_transform)) = enemy_query.get_mut(enemy_entity) else { continue 'attack_loop };
-
-                let attack_dmg = attack.base_dmg + rng.gen_range(0..attack.extra_dmg);
-                enemy.hp -= attack_dmg;
-                damage_number_writer.send(effects::DamageNumberEvent {
-                    dmg: attack_dmg,
-                    position: enemy_transform.translation,
-                });
-
-                if enemy.hp <= 0 {
-                    spawn_gem(&mut commands, &asset_server, enemy_transform.translation);
-                    commands.entity(enemy_entity).despawn();
-                }
+            if !intersecting {
+                continue;
+            }
+            let enemy_entity = if collider1 == attack_entity {
+                collider2
+            } else {
+                collider1
+            };
+            let Ok((mut enemy, enemy_transform, mut enemy_effects)) =
+                enemy_query.get_mut(enemy_entity)
+            else {
+                continue;
+            };
+            // Already killed by another attack this frame.
+            if enemy.hp <= 0 || !attack.can_hit(enemy_entity) {
+                continue;
+            }
+            let used_up = attack.register_hit(enemy_entity);
+
+            let (attack_dmg, crit) = attack.roll_damage(&mut rng);
+            enemy.hp -= attack_dmg;
+            run_stats.damage_dealt += attack_dmg;
+            damage_number_writer.send(effects::DamageNumberEvent {
+                dmg: attack_dmg,
+                position: enemy_transform.translation,
+                crit,
+                damage_type: attack.damage_type,
+                target: enemy_entity,
+            });
+            if let Some(effect) = attack.effect {
+                enemy_effects.apply(effect);
+            }
+            if attack.knockback > 0.0 {
+                let away = (enemy_transform.translation - attack_transform.translation())
+                    .truncate()
+                    .normalize_or_zero();
+                enemy_effects.knock_back(away * attack.knockback);
+            }
 
-                // Only damage the first enemy that gets hit.
+            if used_up {
                 commands.entity(attack_entity).despawn();
-                continue 'attack_loop;
+                break;
             }
         }
     }
 }
 
-fn pickup_gems(
+/// Enemies can be killed by attacks or pickups, their drops are all handled here.
+fn despawn_dead_enemies(
     mut commands: Commands,
-    rapier_context: Res<RapierContext>,
-    mut player_query: Query<(Entity, &mut Player)>,
+    asset_server: Res<AssetServer>,
+    player_query: Query<&PlayerStats>,
+    enemy_query: Query<(Entity, &Enemy, &Transform, Option<&Elite>)>,
+    mut run_stats: ResMut<RunStats>,
 ) {
-    let Some((player_entity, mut player)) = player_query.iter_mut().next() else { return };
-    for (collider1, collider2, intersecting) in rapier_context.intersections_with(player_entity) {
-        if intersecting {
-            let gem_entity = if collider1 == player_entity {
-                collider2
-            } else {
-                collider1
-            };
-            commands.entity(gem_entity).despawn();
-            player.curr_exp += GEM_EXP;
+    let luck = player_query
+        .iter()
+        .next()
+        .map_or(1.0, |stats| stats.get(Stat::Luck));
+    let mut rng = rand::thread_rng();
+
+    for (enemy_entity, enemy, enemy_transform, elite) in &enemy_query {
+        if enemy.hp > 0 {
+            continue;
+        }
+        run_stats.kills += 1;
+        gems::spawn_gem(
+            &mut commands,
+            &asset_server,
+            enemy_transform.translation,
+            enemy.exp,
+        );
+        if elite.is_some() {
+            chest::spawn_chest(&mut commands, &asset_server, enemy_transform.translation);
         }
+        pickups::roll_drop(
+            &mut commands,
+            &asset_server,
+            enemy_transform.translation,
+            enemy.drops,
+            luck,
+            &mut rng,
+        );
+        commands.entity(enemy_entity).despawn();
     }
 }
 
-fn level_up(
-    mut player_query: Query<&mut Player>,
-    mut rapier_config: ResMut<RapierConfiguration>,
-    mut state: ResMut<NextState<GameState>>,
-) {
+fn level_up(mut player_query: Query<&mut Player>, mut state: ResMut<NextState<GameState>>) {
     let Some(mut player) = player_query.iter_mut().next() else { return };
     if player.curr_exp >= player.next_exp {
         player.lvl += 1;
         player.curr_exp -= player.next_exp;
         player.next_exp = ((((player.lvl as f32).log(10.0)) + player.lvl as f32) * 100.0) as i32;
-
-        rapier_config.physics_pipeline_active = false;
         state.set(GameState::LevellingUp);
     }
 }
 
-fn spawn_gem(commands: &mut Commands, asset_server: &Res<AssetServer>, enemy_position: Vec3) {
-    commands.spawn((
-        SpriteBundle {
-            texture: asset_server.load("gem.png"),
-            transform: Transform {
-                translation: enemy_position,
-                scale: Vec3::splat(0.6),
-                ..default()
-            },
-            ..default()
-        },
-        RigidBody::Dynamic,
-        Sensor,
-        Collider::ball(10.0),
-        CollisionGroups::new(physics_groups::PICKUP_GROUP, physics_groups::PLAYER_GROUP),
-        Velocity::default(),
-    ));
-}
-
 fn animate_hp_bar(
     player_query: Query<&Player, Changed<Player>>,
     mut bar_transform_query: Query<&mut Transform, With<PlayerHpBar>>,
@@ -512,112 +454,6 @@ fn animate_exp_bar(
         (player.curr_exp as f32 / player.next_exp as f32).min(1.0) * PLAYER_EXP_WIDTH;
 }
 
-fn launch_fireball(
-    commands: Commands,
-    time: Res<Time>,
-    asset_server: Res<AssetServer>,
-    mut weapon_query: Query<&mut FireballWeapon>,
-    player_transform_query: Query<&Transform, With<Player>>,
-    enemy_transform_query: Query<&Transform, With<Enemy>>,
-) {
-    let Some(mut weapon) = weapon_query.iter_mut().next() else { return };
-
-    weapon.spawn_timer.tick(time.delta());
-    if weapon.spawn_timer.just_finished() {
-        let Some(player_position) = player_transform_query.iter().next().map(|transform| transform.translation.truncate()) else { return };
-        let Some(relative_enemy_position) = enemy_transform_query.iter()
-            .map(|transform| transform.translation.truncate() - player_position)
-            .reduce(|closest_relative_position, relative_position| {
-                if relative_position.length() < closest_relative_position.length() {
-                    relative_position
-                } else {
-                    closest_relative_position
-                }
-            }) else { return };
-        let rotation_radians =
-            relative_enemy_position.y.atan2(relative_enemy_position.x) + PI / 2.0;
-
-        spawn_fireball(
-            commands,
-            asset_server,
-            player_position.extend(1.0),
-            rotation_radians,
-            relative_enemy_position.normalize(),
-            weapon.base_dmg,
-            weapon.extra_dmg,
-        );
-    }
-}
-
-fn spawn_fireball(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    position: Vec3,
-    rotation_radians: f32,
-    direction: Vec2,
-    base_damage: i32,
-    extra_damage: i32,
-) {
-    commands.spawn((
-        Attack {
-            base_dmg: base_damage,
-            extra_dmg: extra_damage,
-        },
-        SpriteBundle {
-            texture: asset_server.load("effects/fireball.png"),
-            transform: Transform {
-                translation: position,
-                rotation: Quat::from_euler(EulerRot::XYZ, 0.0, 0.0, rotation_radians),
-                ..default()
-            },
-            ..default()
-        },
-        RigidBody::Dynamic,
-        Sensor,
-        Collider::ball(10.0),
-        CollisionGroups::new(physics_groups::ATTACK_GROUP, physics_groups::ENEMY_GROUP),
-        Velocity::linear(direction * FIREBALL_SPEED),
-    ));
-}
-
-fn camera_follow_player(
-    mut camera_transform_query: Query<&mut Transform, (With<Camera>, Without<Player>)>,
-    player_transform_query: Query<&Transform, With<Player>>,
-) {
-    info!("Camera follow player running");
-    let Some(mut camera_transform) = camera_transform_query.iter_mut().next() else { return };
-    info!("There is a camera: ${}", camera_transform.translation);
-    let Some(player_transform) = player_transform_query.iter().next() else { return };
-    info!("There is a player: ${}", player_transform.translation);
-
-    camera_transform.translation.x = player_transform.translation.x;
-    camera_transform.translation.y = player_transform.translation.y;
-}
-
-fn pause_game(
-    mut keyboard_input: ResMut<Input<KeyCode>>,
-    mut state: ResMut<NextState<GameState>>,
-    mut rapier_config: ResMut<RapierConfiguration>,
-) {
-    if keyboard_input.just_pressed(KeyCode::Space) {
-        rapier_config.physics_pipeline_active = false;
-        state.set(GameState::Paused);
-        keyboard_input.reset(KeyCode::Space);
-    }
-}
-
-fn unpause_game(
-    mut keyboard_input: ResMut<Input<KeyCode>>,
-    mut state: ResMut<NextState<GameState>>,
-    mut rapier_config: ResMut<RapierConfiguration>,
-) {
-    if keyboard_input.just_pressed(KeyCode::Space) {
-        rapier_config.physics_pipeline_active = true;
-        state.set(GameState::Playing);
-        keyboard_input.reset(KeyCode::Space);
-    }
-}
-
 fn main() {
     App::new()
         .add_state::<GameState>()
@@ -638,42 +474,75 @@ fn main() {
             gravity: Vect::new(0.0, 0.0),
             ..default()
         })
-        .insert_resource(PlayerHitCooldown(HashMap::default()))
         .insert_resource(effects::ActiveDamageEffects(HashMap::default()))
         .add_plugin(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
         .add_plugin(RapierDebugRenderPlugin::default())
         .add_startup_system(global_setup)
-        .add_startup_systems((setup_background, setup_player, setup_spawns))
-        // TODO: Do not run when other states are active
+        .add_systems(
+            (setup_player, spawn_director::setup_spawns)
+                .distributive_run_if(run_not_started)
+                .in_schedule(OnEnter(GameState::Playing)),
+        )
         .add_systems(
             (
-                spawn_enemies,
                 move_player,
-                move_towards_player,
                 animate_loops,
                 animate_player,
-                launch_fireball,
                 attack_enemy_collisions,
+                despawn_dead_enemies.after(attack_enemy_collisions),
                 effects::display_damage_numbers.after(attack_enemy_collisions),
                 effects::animate_damage_numbers,
                 effects::remove_damage_numbers.after(effects::display_damage_numbers),
                 level_up,
-                pickup_gems.after(level_up),
-                animate_exp_bar.after(pickup_gems),
-                player_enemy_collisions.after(attack_enemy_collisions),
-                animate_hp_bar.after(player_enemy_collisions),
+                gems::pickup_gems.after(level_up),
+                animate_exp_bar.after(gems::pickup_gems),
+                player_enemy_collisions
+                    .after(attack_enemy_collisions)
+                    .before(PlayerDamageSet::Receive),
+                animate_hp_bar.after(PlayerDamageSet::Apply),
             )
-                .in_set(OnUpdate(GameState::Playing)),
+                .in_set(SimulationSet),
+        )
+        .add_system(
+            level_up_menu::add_level_up_menu
+                .after(upgrades::roll_upgrade_choices)
+                .in_schedule(OnEnter(GameState::LevellingUp)),
         )
-        .add_systems(())
-        .add_system(level_up_menu::add_level_up_menu.in_schedule(OnEnter(GameState::LevellingUp)))
         .add_system(level_up_menu::handle_choice.in_set(OnUpdate(GameState::LevellingUp)))
         .add_system(level_up_menu::remove_level_up_menu.in_schedule(OnExit(GameState::LevellingUp)))
-        .add_system(pause_game.in_set(OnUpdate(GameState::Playing)))
-        .add_system(unpause_game.in_set(OnUpdate(GameState::Paused)))
-        .add_system(bevy::window::close_on_esc)
+        .add_system(game_over::cleanup_run.in_schedule(OnEnter(GameState::MainMenu)))
+        // Escape pauses during a run, it only quits from the main menu.
+        .add_system(bevy::window::close_on_esc.in_set(OnUpdate(GameState::MainMenu)))
+        .add_plugin(SettingsPlugin)
+        .add_plugin(ActionsPlugin)
+        .add_plugin(SimulationPlugin)
         .add_plugin(CameraPlugin)
+        .add_plugin(BackgroundPlugin)
+        .add_plugin(EnemiesPlugin)
+        .add_plugin(EnemyMovementPlugin)
+        .add_plugin(BossPlugin)
+        .add_plugin(ChestPlugin)
+        .add_plugin(MainMenuPlugin)
+        .add_plugin(MenuNavigationPlugin)
+        .add_plugin(PauseMenuPlugin)
+        .add_plugin(OptionsPlugin)
+        .add_plugin(ControlsPlugin)
+        .add_plugin(CharactersPlugin)
+        .add_plugin(SpawnDirectorPlugin)
+        .add_plugin(WeaponPlugin)
+        .add_plugin(FireballWeaponPlugin)
         .add_plugin(CatWeaponPlugin)
         .add_plugin(BgmPlugin)
+        .add_plugin(SfxPlugin)
+        .add_plugin(UpgradesPlugin)
+        .add_plugin(GameOverPlugin)
+        .add_plugin(GemsPlugin)
+        .add_plugin(PickupsPlugin)
+        .add_plugin(PropsPlugin)
+        .add_plugin(StatsPlugin)
+        .add_plugin(StatusEffectsPlugin)
+        .add_plugin(PlayerDamagePlugin)
+        .add_plugin(PassiveItemsPlugin)
+        .add_plugin(HudPlugin)
         .run();
 }
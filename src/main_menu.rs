@@ -0,0 +1,141 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+
+use crate::{characters::CharacterSelectMode, menu_navigation::Focusable, GameState};
+
+#[derive(Component)]
+struct MainMenuScreen;
+
+#[derive(Component, Clone, Copy)]
+enum MainMenuButton {
+    Start,
+    Characters,
+    Options,
+    Quit,
+}
+
+const MAIN_MENU_BUTTONS: [(MainMenuButton, &str); 4] = [
+    (MainMenuButton::Start, "Start"),
+    (MainMenuButton::Characters, "Characters"),
+    (MainMenuButton::Options, "Options"),
+    (MainMenuButton::Quit, "Quit"),
+];
+
+fn add_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            MainMenuScreen,
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    size: Size {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                    },
+                    padding: UiRect::all(Val::Px(40.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                ..default()
+            },
+        ))
+        .with_children(|top_level| {
+            top_level
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size {
+                            width: Val::Percent(100.0),
+                            height: Val::Px(60.0),
+                        },
+                        margin: UiRect {
+                            top: Val::Px(40.0),
+                            bottom: Val::Px(40.0),
+                            ..default()
+                        },
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.6, 0.1, 0.1).into(),
+                    ..default()
+                })
+                .with_children(|node| {
+                    node.spawn(TextBundle::from_section(
+                        "Billions Must Die!",
+                        TextStyle {
+                            font: asset_server.load("pixel_font.ttf"),
+                            font_size: 32.0,
+                            ..default()
+                        },
+                    ));
+                });
+            for (order, (button, label)) in MAIN_MENU_BUTTONS.into_iter().enumerate() {
+                top_level
+                    .spawn((
+                        button,
+                        Focusable { order },
+                        ButtonBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(200.0), Val::Px(40.0)),
+                                margin: UiRect::bottom(Val::Px(12.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: Color::rgb(0.15, 0.15, 1.00).into(),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|button| {
+                        button.spawn(TextBundle::from_section(
+                            label,
+                            TextStyle {
+                                font: asset_server.load("pixel_font.ttf"),
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+fn handle_main_menu(
+    interaction_query: Query<(&Interaction, &MainMenuButton), Changed<Interaction>>,
+    mut select_mode: ResMut<CharacterSelectMode>,
+    mut state: ResMut<NextState<GameState>>,
+    mut exit_writer: EventWriter<AppExit>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        match button {
+            MainMenuButton::Start => {
+                *select_mode = CharacterSelectMode::StartRun;
+                state.set(GameState::CharacterSelect);
+            }
+            MainMenuButton::Characters => {
+                *select_mode = CharacterSelectMode::Browse;
+                state.set(GameState::CharacterSelect);
+            }
+            MainMenuButton::Options => state.set(GameState::Options),
+            MainMenuButton::Quit => exit_writer.send(AppExit),
+        }
+    }
+}
+
+fn remove_main_menu(mut commands: Commands, screen_query: Query<Entity, With<MainMenuScreen>>) {
+    let Some(screen_entity) = screen_query.iter().next() else { return };
+    commands.entity(screen_entity).despawn_recursive();
+}
+
+pub struct MainMenuPlugin;
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(add_main_menu.in_schedule(OnEnter(GameState::MainMenu)))
+            .add_system(handle_main_menu.in_set(OnUpdate(GameState::MainMenu)))
+            .add_system(remove_main_menu.in_schedule(OnExit(GameState::MainMenu)));
+    }
+}
@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+
+use crate::actions::{Action, ActionInput};
+
+const BUTTON_COLOR: Color = Color::rgb(0.15, 0.15, 1.00);
+const FOCUSED_BUTTON_COLOR: Color = Color::rgb(0.4, 0.4, 1.0);
+
+/// Buttons that can be reached with the keyboard or a gamepad, visited in increasing `order`.
+#[derive(Component)]
+pub struct Focusable {
+    pub order: usize,
+}
+
+/// Position of the focused button among the focusable buttons on screen.
+#[derive(Resource, Default)]
+struct MenuFocus(usize);
+
+/// Moves the focus with the direction actions and presses the focused button on confirm, the mouse
+/// moves the focus to the hovered button.
+fn navigate_menus(
+    action_input: Res<ActionInput>,
+    mut focus: ResMut<MenuFocus>,
+    added_query: Query<(), Added<Focusable>>,
+    mut button_query: Query<(&Focusable, &mut Interaction, &mut BackgroundColor)>,
+) {
+    let mut buttons: Vec<_> = button_query.iter_mut().collect();
+    if buttons.is_empty() {
+        return;
+    }
+    buttons.sort_by_key(|(focusable, _, _)| focusable.order);
+    if !added_query.is_empty() {
+        focus.0 = 0;
+    }
+    if let Some(hovered) = buttons.iter().position(|(_, interaction, _)| {
+        interaction.is_changed() && **interaction == Interaction::Hovered
+    }) {
+        focus.0 = hovered;
+    }
+
+    // Menus are laid out in a single row or column, so both axes move through the same list.
+    let previous = action_input.just_pressed(Action::Up) || action_input.just_pressed(Action::Left);
+    let next = action_input.just_pressed(Action::Down) || action_input.just_pressed(Action::Right);
+    let count = buttons.len();
+    if previous {
+        focus.0 = (focus.0 + count - 1) % count;
+    } else if next {
+        focus.0 = (focus.0 + 1) % count;
+    }
+    focus.0 = focus.0.min(count - 1);
+
+    let confirm = action_input.just_pressed(Action::Confirm);
+    for (index, (_, interaction, background_color)) in buttons.iter_mut().enumerate() {
+        let color = if index == focus.0 {
+            FOCUSED_BUTTON_COLOR
+        } else {
+            BUTTON_COLOR
+        };
+        if background_color.0 != color {
+            background_color.0 = color;
+        }
+        // Menus react to `Interaction` changes, so pressing a button looks like a click.
+        if index == focus.0 && confirm {
+            **interaction = Interaction::Clicked;
+        }
+    }
+}
+
+pub struct MenuNavigationPlugin;
+
+impl Plugin for MenuNavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuFocus>().add_system(navigate_menus);
+    }
+}
@@ -0,0 +1,251 @@
+use bevy::prelude::*;
+
+use crate::{
+    actions::{Action, ActionInput},
+    menu_navigation::Focusable,
+    settings::Settings,
+    GameState, Player,
+};
+
+const VOLUME_STEPS: i32 = 10;
+const WINDOW_SCALES: [f32; 3] = [1.0, 1.5, 2.0];
+const SCREEN_SHAKE_LEVELS: [f32; 3] = [0.0, 0.5, 1.0];
+
+#[derive(Component)]
+struct OptionsScreen;
+
+#[derive(Component, Clone, Copy)]
+enum OptionsButton {
+    MusicVolume,
+    SfxVolume,
+    Fullscreen,
+    WindowScale,
+    DamageNumbers,
+    AggregateDamageNumbers,
+    ScreenShake,
+    Controls,
+    Back,
+}
+
+const OPTIONS_BUTTONS: [OptionsButton; 9] = [
+    OptionsButton::MusicVolume,
+    OptionsButton::SfxVolume,
+    OptionsButton::Fullscreen,
+    OptionsButton::WindowScale,
+    OptionsButton::DamageNumbers,
+    OptionsButton::AggregateDamageNumbers,
+    OptionsButton::ScreenShake,
+    OptionsButton::Controls,
+    OptionsButton::Back,
+];
+
+/// Text showing the current value of a setting.
+#[derive(Component)]
+struct OptionValue(OptionsButton);
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "On"
+    } else {
+        "Off"
+    }
+}
+
+fn percent(value: f32) -> i32 {
+    (value * 100.0).round() as i32
+}
+
+fn option_value(button: OptionsButton, settings: &Settings) -> String {
+    match button {
+        OptionsButton::MusicVolume => format!("Music volume: {}%", percent(settings.music_volume)),
+        OptionsButton::SfxVolume => format!("Sound volume: {}%", percent(settings.sfx_volume)),
+        OptionsButton::Fullscreen => format!("Fullscreen: {}", on_off(settings.fullscreen)),
+        OptionsButton::WindowScale => format!("Window scale: {}x", settings.window_scale),
+        OptionsButton::DamageNumbers => {
+            format!("Damage numbers: {}", on_off(settings.damage_numbers))
+        }
+        OptionsButton::AggregateDamageNumbers => format!(
+            "Combine damage numbers: {}",
+            on_off(settings.aggregate_damage_numbers)
+        ),
+        OptionsButton::ScreenShake => format!("Screen shake: {}%", percent(settings.screen_shake)),
+        OptionsButton::Controls => "Controls".to_string(),
+        OptionsButton::Back => "Back".to_string(),
+    }
+}
+
+/// Volumes go up in tenths and wrap back to 0 after 100%.
+fn next_volume(volume: f32) -> f32 {
+    let step = (volume * VOLUME_STEPS as f32).round() as i32 + 1;
+    if step > VOLUME_STEPS {
+        0.0
+    } else {
+        step as f32 / VOLUME_STEPS as f32
+    }
+}
+
+/// The first level above `value`, starting over from the lowest one.
+fn next_level(levels: &[f32], value: f32) -> f32 {
+    levels
+        .iter()
+        .copied()
+        .find(|level| *level > value)
+        .unwrap_or(levels[0])
+}
+
+fn add_options_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+) {
+    commands
+        .spawn((
+            OptionsScreen,
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    size: Size {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                    },
+                    padding: UiRect::all(Val::Px(30.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                ..default()
+            },
+        ))
+        .with_children(|top_level| {
+            top_level
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size {
+                            width: Val::Percent(100.0),
+                            height: Val::Px(40.0),
+                        },
+                        margin: UiRect {
+                            bottom: Val::Px(16.0),
+                            ..default()
+                        },
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.15, 0.15, 1.00).into(),
+                    ..default()
+                })
+                .with_children(|node| {
+                    node.spawn(TextBundle::from_section(
+                        "Options",
+                        TextStyle {
+                            font: asset_server.load("pixel_font.ttf"),
+                            ..default()
+                        },
+                    ));
+                });
+            for (order, button) in OPTIONS_BUTTONS.into_iter().enumerate() {
+                top_level
+                    .spawn((
+                        button,
+                        Focusable { order },
+                        ButtonBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(100.0), Val::Px(32.0)),
+                                margin: UiRect::bottom(Val::Px(4.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: Color::rgb(0.15, 0.15, 1.00).into(),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|button_node| {
+                        button_node.spawn((
+                            OptionValue(button),
+                            TextBundle::from_section(
+                                option_value(button, &settings),
+                                TextStyle {
+                                    font: asset_server.load("pixel_font.ttf"),
+                                    font_size: 16.0,
+                                    ..default()
+                                },
+                            ),
+                        ));
+                    });
+            }
+        });
+}
+
+fn handle_options(
+    interaction_query: Query<(&Interaction, &OptionsButton), Changed<Interaction>>,
+    action_input: Res<ActionInput>,
+    mut settings: ResMut<Settings>,
+    player_query: Query<(), With<Player>>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    // Options are opened from the pause menu during a run.
+    let back_state = if player_query.is_empty() {
+        GameState::MainMenu
+    } else {
+        GameState::Paused
+    };
+    if action_input.just_pressed(Action::Cancel) {
+        state.set(back_state);
+    }
+    for (interaction, button) in &interaction_query {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        match button {
+            OptionsButton::MusicVolume => {
+                settings.music_volume = next_volume(settings.music_volume)
+            }
+            OptionsButton::SfxVolume => settings.sfx_volume = next_volume(settings.sfx_volume),
+            OptionsButton::Fullscreen => settings.fullscreen = !settings.fullscreen,
+            OptionsButton::WindowScale => {
+                settings.window_scale = next_level(&WINDOW_SCALES, settings.window_scale);
+            }
+            OptionsButton::DamageNumbers => settings.damage_numbers = !settings.damage_numbers,
+            OptionsButton::AggregateDamageNumbers => {
+                settings.aggregate_damage_numbers = !settings.aggregate_damage_numbers;
+            }
+            OptionsButton::ScreenShake => {
+                settings.screen_shake = next_level(&SCREEN_SHAKE_LEVELS, settings.screen_shake);
+            }
+            OptionsButton::Controls => state.set(GameState::Controls),
+            OptionsButton::Back => state.set(back_state),
+        }
+    }
+}
+
+fn refresh_option_values(
+    settings: Res<Settings>,
+    mut value_query: Query<(&OptionValue, &mut Text)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for (value, mut text) in &mut value_query {
+        text.sections[0].value = option_value(value.0, &settings);
+    }
+}
+
+fn remove_options_screen(mut commands: Commands, screen_query: Query<Entity, With<OptionsScreen>>) {
+    let Some(screen_entity) = screen_query.iter().next() else { return };
+    commands.entity(screen_entity).despawn_recursive();
+}
+
+pub struct OptionsPlugin;
+
+impl Plugin for OptionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(add_options_screen.in_schedule(OnEnter(GameState::Options)))
+            .add_systems(
+                (handle_options, refresh_option_values.after(handle_options))
+                    .in_set(OnUpdate(GameState::Options)),
+            )
+            .add_system(remove_options_screen.in_schedule(OnExit(GameState::Options)));
+    }
+}
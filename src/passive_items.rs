@@ -0,0 +1,367 @@
+use bevy::prelude::*;
+
+use crate::{
+    effects::{DamageNumberEvent, DamageType},
+    enemies::Enemy,
+    game_over::RunStats,
+    player_damage::{IncomingHit, PlayerDamageSet, PlayerDamagedEvent},
+    simulation::{GameClock, SimulationSet},
+    stats::{PlayerStats, Stat, StatModifier},
+    Player,
+};
+
+pub const MAX_PASSIVE_ITEMS: usize = 6;
+const SHIELD_RECHARGE_TIME: f32 = 15.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PassiveKind {
+    Spinach,
+    EmptyTome,
+    Armor,
+    Pummarola,
+    Wings,
+    Attractorb,
+    Candelabrador,
+    Bracer,
+    Spellbinder,
+    Duplicator,
+    Clover,
+    Crown,
+    StoneMask,
+    ThornMail,
+    Aegis,
+}
+
+pub const PASSIVE_KINDS: [PassiveKind; 15] = [
+    PassiveKind::Spinach,
+    PassiveKind::EmptyTome,
+    PassiveKind::Armor,
+    PassiveKind::Pummarola,
+    PassiveKind::Wings,
+    PassiveKind::Attractorb,
+    PassiveKind::Candelabrador,
+    PassiveKind::Bracer,
+    PassiveKind::Spellbinder,
+    PassiveKind::Duplicator,
+    PassiveKind::Clover,
+    PassiveKind::Crown,
+    PassiveKind::StoneMask,
+    PassiveKind::ThornMail,
+    PassiveKind::Aegis,
+];
+
+pub struct PassiveDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub icon: &'static str,
+    pub icon_color: Color,
+    /// How likely the item is to be offered when levelling up.
+    pub weight: f32,
+    pub max_rank: usize,
+    /// Applied once for every rank.
+    pub modifier: StatModifier,
+}
+
+const SPINACH: PassiveDefinition = PassiveDefinition {
+    name: "Spinach",
+    description: "Increases damage by 10%.",
+    icon: "gem.png",
+    icon_color: Color::rgb(1.0, 0.5, 0.1),
+    weight: 1.5,
+    max_rank: 5,
+    modifier: StatModifier::Multiply(Stat::Might, 1.1),
+};
+
+const EMPTY_TOME: PassiveDefinition = PassiveDefinition {
+    name: "Empty Tome",
+    description: "Weapons cool down 8% faster.",
+    icon: "gem.png",
+    icon_color: Color::rgb(0.9, 0.9, 0.9),
+    weight: 1.0,
+    max_rank: 5,
+    modifier: StatModifier::Multiply(Stat::Cooldown, 0.92),
+};
+
+const ARMOR: PassiveDefinition = PassiveDefinition {
+    name: "Armor",
+    description: "Reduces damage taken by 1.",
+    icon: "gem.png",
+    icon_color: Color::rgb(0.6, 0.6, 0.7),
+    weight: 1.0,
+    max_rank: 5,
+    modifier: StatModifier::Add(Stat::Armor, 1.0),
+};
+
+const PUMMAROLA: PassiveDefinition = PassiveDefinition {
+    name: "Pummarola",
+    description: "Recovers 0.2 HP per second.",
+    icon: "gem.png",
+    icon_color: Color::rgb(0.9, 0.2, 0.4),
+    weight: 1.0,
+    max_rank: 5,
+    modifier: StatModifier::Add(Stat::Regen, 0.2),
+};
+
+const WINGS: PassiveDefinition = PassiveDefinition {
+    name: "Wings",
+    description: "Increases movement speed by 10%.",
+    icon: "gem.png",
+    icon_color: Color::rgb(0.5, 0.9, 1.0),
+    weight: 1.0,
+    max_rank: 5,
+    modifier: StatModifier::Multiply(Stat::MoveSpeed, 1.1),
+};
+
+const ATTRACTORB: PassiveDefinition = PassiveDefinition {
+    name: "Attractorb",
+    description: "Increases gem pickup radius by 25%.",
+    icon: "gem.png",
+    icon_color: Color::rgb(0.4, 0.6, 1.0),
+    weight: 1.5,
+    max_rank: 5,
+    modifier: StatModifier::Multiply(Stat::Magnet, 1.25),
+};
+
+const CANDELABRADOR: PassiveDefinition = PassiveDefinition {
+    name: "Candelabrador",
+    description: "Increases projectile size by 10%.",
+    icon: "gem.png",
+    icon_color: Color::rgb(1.0, 0.9, 0.4),
+    weight: 1.0,
+    max_rank: 5,
+    modifier: StatModifier::Multiply(Stat::Area, 1.1),
+};
+
+const BRACER: PassiveDefinition = PassiveDefinition {
+    name: "Bracer",
+    description: "Projectiles fly 10% faster.",
+    icon: "gem.png",
+    icon_color: Color::rgb(0.7, 0.4, 0.2),
+    weight: 1.0,
+    max_rank: 5,
+    modifier: StatModifier::Multiply(Stat::ProjectileSpeed, 1.1),
+};
+
+const SPELLBINDER: PassiveDefinition = PassiveDefinition {
+    name: "Spellbinder",
+    description: "Projectiles last 10% longer.",
+    icon: "gem.png",
+    icon_color: Color::rgb(0.5, 0.3, 0.9),
+    weight: 1.0,
+    max_rank: 5,
+    modifier: StatModifier::Multiply(Stat::Duration, 1.1),
+};
+
+const DUPLICATOR: PassiveDefinition = PassiveDefinition {
+    name: "Duplicator",
+    description: "Weapons fire one more projectile.",
+    icon: "gem.png",
+    icon_color: Color::rgb(0.3, 0.9, 0.9),
+    weight: 0.5,
+    max_rank: 2,
+    modifier: StatModifier::Add(Stat::Amount, 1.0),
+};
+
+const CLOVER: PassiveDefinition = PassiveDefinition {
+    name: "Clover",
+    description: "Increases luck by 10%.",
+    icon: "gem.png",
+    icon_color: Color::rgb(0.2, 0.8, 0.2),
+    weight: 1.0,
+    max_rank: 5,
+    modifier: StatModifier::Add(Stat::Luck, 0.1),
+};
+
+const CROWN: PassiveDefinition = PassiveDefinition {
+    name: "Crown",
+    description: "Increases experience gained by 8%.",
+    icon: "gem.png",
+    icon_color: Color::rgb(1.0, 0.85, 0.0),
+    weight: 1.0,
+    max_rank: 5,
+    modifier: StatModifier::Add(Stat::Growth, 0.08),
+};
+
+const STONE_MASK: PassiveDefinition = PassiveDefinition {
+    name: "Stone Mask",
+    description: "Increases gold gained by 10%.",
+    icon: "gem.png",
+    icon_color: Color::rgb(0.5, 0.5, 0.4),
+    weight: 1.0,
+    max_rank: 5,
+    modifier: StatModifier::Add(Stat::Greed, 0.1),
+};
+
+const THORN_MAIL: PassiveDefinition = PassiveDefinition {
+    name: "Thorn Mail",
+    description: "Enemies that hit you take 5 damage.",
+    icon: "gem.png",
+    icon_color: Color::rgb(0.4, 0.5, 0.2),
+    weight: 0.8,
+    max_rank: 5,
+    modifier: StatModifier::Add(Stat::Thorns, 5.0),
+};
+
+const AEGIS: PassiveDefinition = PassiveDefinition {
+    name: "Aegis",
+    description: "Blocks one more hit, recharges over time.",
+    icon: "gem.png",
+    icon_color: Color::rgb(0.9, 0.9, 1.0),
+    weight: 0.5,
+    max_rank: 3,
+    modifier: StatModifier::Add(Stat::Shield, 1.0),
+};
+
+impl PassiveKind {
+    pub fn definition(self) -> &'static PassiveDefinition {
+        match self {
+            PassiveKind::Spinach => &SPINACH,
+            PassiveKind::EmptyTome => &EMPTY_TOME,
+            PassiveKind::Armor => &ARMOR,
+            PassiveKind::Pummarola => &PUMMAROLA,
+            PassiveKind::Wings => &WINGS,
+            PassiveKind::Attractorb => &ATTRACTORB,
+            PassiveKind::Candelabrador => &CANDELABRADOR,
+            PassiveKind::Bracer => &BRACER,
+            PassiveKind::Spellbinder => &SPELLBINDER,
+            PassiveKind::Duplicator => &DUPLICATOR,
+            PassiveKind::Clover => &CLOVER,
+            PassiveKind::Crown => &CROWN,
+            PassiveKind::StoneMask => &STONE_MASK,
+            PassiveKind::ThornMail => &THORN_MAIL,
+            PassiveKind::Aegis => &AEGIS,
+        }
+    }
+}
+
+/// Passive items are children of the player like weapons, one entity per item kind.
+#[derive(Component)]
+pub struct PassiveItem {
+    pub kind: PassiveKind,
+    pub rank: usize,
+}
+
+pub struct UpgradePassiveEvent(pub PassiveKind);
+
+fn upgrade_passive_items(
+    mut commands: Commands,
+    mut upgrade_passive_reader: EventReader<UpgradePassiveEvent>,
+    mut player_query: Query<(Entity, &mut PlayerStats), With<Player>>,
+    mut passive_query: Query<&mut PassiveItem>,
+) {
+    let Some((player_entity, mut stats)) = player_query.iter_mut().next() else { return };
+
+    // Items added this frame aren't spawned until the commands run, so repeats rank these up.
+    let mut new_items: Vec<PassiveItem> = Vec::new();
+    for UpgradePassiveEvent(kind) in upgrade_passive_reader.iter() {
+        let definition = kind.definition();
+        let owned_item = passive_query.iter_mut().find(|item| item.kind == *kind);
+        let rank = match owned_item {
+            Some(item) => &mut item.into_inner().rank,
+            None => match new_items.iter_mut().find(|item| item.kind == *kind) {
+                Some(item) => &mut item.rank,
+                None => {
+                    new_items.push(PassiveItem {
+                        kind: *kind,
+                        rank: 0,
+                    });
+                    &mut new_items.last_mut().unwrap().rank
+                }
+            },
+        };
+        if *rank >= definition.max_rank {
+            continue;
+        }
+        *rank += 1;
+        stats.add_modifier(definition.modifier);
+    }
+    if new_items.is_empty() {
+        return;
+    }
+    commands.entity(player_entity).with_children(|parent| {
+        for item in new_items {
+            parent.spawn(item);
+        }
+    });
+}
+
+/// Shield charges left and progress towards the next one.
+#[derive(Resource, Default)]
+pub struct ShieldState {
+    charges: u32,
+    /// The max the last frame, new charges from a higher max are ready right away.
+    max_charges: u32,
+    recharge: f32,
+}
+
+fn block_hits_with_shield(
+    time: Res<GameClock>,
+    player_query: Query<&PlayerStats, With<Player>>,
+    mut incoming_hit: ResMut<IncomingHit>,
+    mut shield: ResMut<ShieldState>,
+) {
+    let Some(stats) = player_query.iter().next() else { return };
+    let max_charges = stats.get(Stat::Shield).max(0.0) as u32;
+    if max_charges > shield.max_charges {
+        shield.charges += max_charges - shield.max_charges;
+    }
+    shield.max_charges = max_charges;
+    shield.charges = shield.charges.min(max_charges);
+    if shield.charges < max_charges {
+        shield.recharge += time.delta_seconds();
+        if shield.recharge >= SHIELD_RECHARGE_TIME {
+            shield.recharge = 0.0;
+            shield.charges += 1;
+        }
+    }
+
+    let Some(hit) = incoming_hit.hit.as_mut() else { return };
+    if shield.charges > 0 && hit.damage > 0 {
+        shield.charges -= 1;
+        hit.damage = 0;
+    }
+}
+
+fn thorns(
+    mut damaged_reader: EventReader<PlayerDamagedEvent>,
+    player_query: Query<&PlayerStats, With<Player>>,
+    mut enemy_query: Query<(&mut Enemy, &Transform)>,
+    mut damage_number_writer: EventWriter<DamageNumberEvent>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    let Some(stats) = player_query.iter().next() else { return };
+    let thorns = stats.get(Stat::Thorns).round() as i32;
+    for damaged in damaged_reader.iter() {
+        if thorns <= 0 {
+            continue;
+        }
+        let Some(source) = damaged.source else { continue };
+        let Ok((mut enemy, transform)) = enemy_query.get_mut(source) else { continue };
+        enemy.hp -= thorns;
+        run_stats.damage_dealt += thorns;
+        damage_number_writer.send(DamageNumberEvent {
+            dmg: thorns,
+            position: transform.translation,
+            crit: false,
+            damage_type: DamageType::Physical,
+            target: source,
+        });
+    }
+}
+
+pub struct PassiveItemsPlugin;
+
+impl Plugin for PassiveItemsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<UpgradePassiveEvent>()
+            .init_resource::<ShieldState>()
+            .add_system(upgrade_passive_items)
+            .add_systems(
+                (
+                    block_hits_with_shield.in_set(PlayerDamageSet::Intercept),
+                    thorns.after(PlayerDamageSet::Apply),
+                )
+                    .in_set(SimulationSet),
+            );
+    }
+}
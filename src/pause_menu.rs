@@ -0,0 +1,243 @@
+use bevy::prelude::*;
+
+use crate::{
+    actions::{Action, ActionInput},
+    game_over::RunStats,
+    menu_navigation::Focusable,
+    passive_items::PassiveItem,
+    weapon::{Weapon, WeaponDefinitions},
+    GameState, Player,
+};
+
+#[derive(Component)]
+struct PauseMenuScreen;
+
+#[derive(Component, Clone, Copy)]
+enum PauseMenuButton {
+    Resume,
+    Options,
+    AbandonRun,
+}
+
+const PAUSE_MENU_BUTTONS: [(PauseMenuButton, &str); 3] = [
+    (PauseMenuButton::Resume, "Resume"),
+    (PauseMenuButton::Options, "Options"),
+    (PauseMenuButton::AbandonRun, "Abandon Run"),
+];
+
+fn pause_game(action_input: Res<ActionInput>, mut state: ResMut<NextState<GameState>>) {
+    if action_input.just_pressed(Action::Pause) {
+        state.set(GameState::Paused);
+    }
+}
+
+fn unpause_game(action_input: Res<ActionInput>, mut state: ResMut<NextState<GameState>>) {
+    if action_input.just_pressed(Action::Pause) || action_input.just_pressed(Action::Cancel) {
+        state.set(GameState::Playing);
+    }
+}
+
+fn spawn_text_column(
+    parent: &mut ChildBuilder,
+    asset_server: &AssetServer,
+    title: &str,
+    lines: Vec<String>,
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                size: Size::new(Val::Percent(50.0), Val::Auto),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|column| {
+            column.spawn(TextBundle::from_section(
+                title,
+                TextStyle {
+                    font: asset_server.load("pixel_font.ttf"),
+                    font_size: 16.0,
+                    ..default()
+                },
+            ));
+            for line in lines {
+                column.spawn(
+                    TextBundle::from_section(
+                        line,
+                        TextStyle {
+                            font: asset_server.load("pixel_font.ttf"),
+                            font_size: 12.0,
+                            ..default()
+                        },
+                    )
+                    .with_style(Style {
+                        margin: UiRect::top(Val::Px(4.0)),
+                        ..default()
+                    }),
+                );
+            }
+        });
+}
+
+fn add_pause_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    run_stats: Res<RunStats>,
+    definitions: Res<WeaponDefinitions>,
+    player_query: Query<&Player>,
+    weapon_query: Query<&Weapon>,
+    passive_query: Query<&PassiveItem>,
+) {
+    let level = player_query.iter().next().map_or(1, |player| player.lvl);
+    let seconds = run_stats.time_survived as u32;
+    let stats = vec![
+        format!("Time: {:02}:{:02}", seconds / 60, seconds % 60),
+        format!("Level: {}", level),
+        format!("Kills: {}", run_stats.kills),
+        format!("Gold: {}", run_stats.gold),
+    ];
+    let weapons = weapon_query
+        .iter()
+        .map(|weapon| format!("{} {}", definitions.get(weapon.kind).name, weapon.level))
+        .collect();
+    let passives = passive_query
+        .iter()
+        .map(|item| format!("{} {}", item.kind.definition().name, item.rank))
+        .collect();
+
+    commands
+        .spawn((
+            PauseMenuScreen,
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    size: Size {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                    },
+                    padding: UiRect::all(Val::Px(30.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                ..default()
+            },
+        ))
+        .with_children(|top_level| {
+            top_level
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size {
+                            width: Val::Percent(100.0),
+                            height: Val::Px(40.0),
+                        },
+                        margin: UiRect {
+                            bottom: Val::Px(16.0),
+                            ..default()
+                        },
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.15, 0.15, 1.00).into(),
+                    ..default()
+                })
+                .with_children(|node| {
+                    node.spawn(TextBundle::from_section(
+                        "Paused",
+                        TextStyle {
+                            font: asset_server.load("pixel_font.ttf"),
+                            ..default()
+                        },
+                    ));
+                });
+            top_level
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.0), Val::Auto),
+                        margin: UiRect::bottom(Val::Px(12.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    spawn_text_column(row, &asset_server, "Run", stats);
+                });
+            top_level
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.0), Val::Auto),
+                        margin: UiRect::bottom(Val::Px(16.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    spawn_text_column(row, &asset_server, "Weapons", weapons);
+                    spawn_text_column(row, &asset_server, "Passives", passives);
+                });
+            for (order, (button, label)) in PAUSE_MENU_BUTTONS.into_iter().enumerate() {
+                top_level
+                    .spawn((
+                        button,
+                        Focusable { order },
+                        ButtonBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(200.0), Val::Px(36.0)),
+                                margin: UiRect::bottom(Val::Px(8.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: Color::rgb(0.15, 0.15, 1.00).into(),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|button| {
+                        button.spawn(TextBundle::from_section(
+                            label,
+                            TextStyle {
+                                font: asset_server.load("pixel_font.ttf"),
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+fn handle_pause_menu(
+    interaction_query: Query<(&Interaction, &PauseMenuButton), Changed<Interaction>>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        state.set(match button {
+            PauseMenuButton::Resume => GameState::Playing,
+            PauseMenuButton::Options => GameState::Options,
+            PauseMenuButton::AbandonRun => GameState::MainMenu,
+        });
+    }
+}
+
+fn remove_pause_menu(mut commands: Commands, screen_query: Query<Entity, With<PauseMenuScreen>>) {
+    let Some(screen_entity) = screen_query.iter().next() else { return };
+    commands.entity(screen_entity).despawn_recursive();
+}
+
+pub struct PauseMenuPlugin;
+
+impl Plugin for PauseMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(pause_game.in_set(OnUpdate(GameState::Playing)))
+            .add_system(add_pause_menu.in_schedule(OnEnter(GameState::Paused)))
+            .add_systems(
+                (unpause_game, handle_pause_menu.after(unpause_game))
+                    .in_set(OnUpdate(GameState::Paused)),
+            )
+            .add_system(remove_pause_menu.in_schedule(OnExit(GameState::Paused)));
+    }
+}
@@ -4,3 +4,5 @@ pub const PLAYER_GROUP: Group = Group::GROUP_1;
 pub const ENEMY_GROUP: Group = Group::GROUP_2;
 pub const ATTACK_GROUP: Group = Group::GROUP_4;
 pub const PICKUP_GROUP: Group = Group::GROUP_5;
+pub const ENEMY_ATTACK_GROUP: Group = Group::GROUP_6;
+pub const PROP_GROUP: Group = Group::GROUP_7;
@@ -0,0 +1,217 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+
+use crate::{
+    camera::ScreenShake,
+    effects::{DamageNumberEvent, DamageType},
+    enemies::Enemy,
+    game_over::RunStats,
+    gems::{self, Gem},
+    physics_groups,
+    props::Prop,
+    simulation::SimulationSet,
+    stats::{PlayerStats, Stat},
+    status_effects::{StatusEffect, StatusEffects, StatusKind},
+    Player, WINDOW_SIZE,
+};
+
+const HEAL_AMOUNT: i32 = 30;
+const GOLD_VALUE: f32 = 10.0;
+const BOMB_DAMAGE: i32 = 50;
+const BOMB_TRAUMA: f32 = 0.6;
+const FREEZE: StatusEffect = StatusEffect {
+    kind: StatusKind::Freeze,
+    strength: 0.0,
+    duration: 5.0,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PickupKind {
+    /// Restores some hp.
+    Heal,
+    /// Pulls every gem on the field to the player.
+    Vacuum,
+    /// Damages every enemy on screen.
+    Bomb,
+    Gold,
+    /// Freezes every enemy on the field.
+    Freeze,
+}
+
+struct PickupDefinition {
+    color: Color,
+    scale: f32,
+}
+
+impl PickupKind {
+    fn definition(self) -> PickupDefinition {
+        match self {
+            PickupKind::Heal => PickupDefinition {
+                color: Color::rgb(1.0, 0.3, 0.4),
+                scale: 0.8,
+            },
+            PickupKind::Vacuum => PickupDefinition {
+                color: Color::rgb(0.3, 0.3, 1.0),
+                scale: 0.8,
+            },
+            PickupKind::Bomb => PickupDefinition {
+                color: Color::rgb(0.2, 0.2, 0.2),
+                scale: 0.9,
+            },
+            PickupKind::Gold => PickupDefinition {
+                color: Color::rgb(1.0, 0.85, 0.1),
+                scale: 0.5,
+            },
+            PickupKind::Freeze => PickupDefinition {
+                color: Color::rgb(0.7, 1.0, 1.0),
+                scale: 0.8,
+            },
+        }
+    }
+}
+
+/// One entry of a drop table, `chance` is out of 1 before luck is applied.
+pub struct PickupDrop {
+    pub kind: PickupKind,
+    pub chance: f32,
+}
+
+#[derive(Component)]
+pub struct Pickup {
+    kind: PickupKind,
+}
+
+pub fn spawn_pickup(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    position: Vec3,
+    kind: PickupKind,
+) {
+    let definition = kind.definition();
+    commands.spawn((
+        Pickup { kind },
+        SpriteBundle {
+            sprite: Sprite {
+                color: definition.color,
+                ..default()
+            },
+            texture: asset_server.load("gem.png"),
+            transform: Transform {
+                translation: position,
+                scale: Vec3::splat(definition.scale),
+                ..default()
+            },
+            ..default()
+        },
+        RigidBody::Dynamic,
+        Sensor,
+        Collider::ball(10.0),
+        CollisionGroups::new(physics_groups::PICKUP_GROUP, physics_groups::PLAYER_GROUP),
+        Velocity::default(),
+    ));
+}
+
+/// Drops at most one pickup from the table, luck scales up every chance.
+pub fn roll_drop(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    position: Vec3,
+    table: &[PickupDrop],
+    luck: f32,
+    rng: &mut impl Rng,
+) {
+    let roll = rng.gen::<f32>() / luck;
+    let mut cumulative = 0.0;
+    for drop in table {
+        cumulative += drop.chance;
+        if roll < cumulative {
+            spawn_pickup(commands, asset_server, position, drop.kind);
+            return;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn collect_pickups(
+    mut commands: Commands,
+    rapier_context: Res<RapierContext>,
+    mut player_query: Query<(Entity, &mut Player, &PlayerStats, &Transform)>,
+    pickup_query: Query<&Pickup>,
+    mut gem_query: Query<&mut Gem>,
+    mut enemy_query: Query<(Entity, &mut Enemy, &Transform, &mut StatusEffects)>,
+    mut prop_query: Query<(&mut Prop, &Transform)>,
+    mut damage_number_writer: EventWriter<DamageNumberEvent>,
+    mut run_stats: ResMut<RunStats>,
+    mut screen_shake: ResMut<ScreenShake>,
+) {
+    let Some((player_entity, mut player, stats, player_transform)) = player_query.iter_mut().next() else { return };
+    for (collider1, collider2, intersecting) in rapier_context.intersections_with(player_entity) {
+        if !intersecting {
+            continue;
+        }
+        let pickup_entity = if collider1 == player_entity {
+            collider2
+        } else {
+            collider1
+        };
+        let Ok(pickup) = pickup_query.get(pickup_entity) else { continue };
+        commands.entity(pickup_entity).despawn();
+
+        match pickup.kind {
+            PickupKind::Heal => player.hp = (player.hp + HEAL_AMOUNT).min(player.max_hp),
+            PickupKind::Vacuum => {
+                for mut gem in &mut gem_query {
+                    gem.attracted = true;
+                }
+            }
+            PickupKind::Bomb => {
+                screen_shake.add_trauma(BOMB_TRAUMA);
+                // The camera is centered on the player so this covers the whole screen.
+                let on_screen = |transform: &Transform| {
+                    let offset = (transform.translation - player_transform.translation).abs();
+                    offset.x <= WINDOW_SIZE / 2.0 && offset.y <= WINDOW_SIZE / 2.0
+                };
+                for (mut prop, prop_transform) in &mut prop_query {
+                    if on_screen(prop_transform) {
+                        prop.broken = true;
+                    }
+                }
+                for (enemy_entity, mut enemy, enemy_transform, _) in &mut enemy_query {
+                    if enemy.hp <= 0 || !on_screen(enemy_transform) {
+                        continue;
+                    }
+                    enemy.hp -= BOMB_DAMAGE;
+                    run_stats.damage_dealt += BOMB_DAMAGE;
+                    damage_number_writer.send(DamageNumberEvent {
+                        dmg: BOMB_DAMAGE,
+                        position: enemy_transform.translation,
+                        crit: false,
+                        damage_type: DamageType::Explosion,
+                        target: enemy_entity,
+                    });
+                }
+            }
+            PickupKind::Gold => {
+                run_stats.gold += (GOLD_VALUE * stats.get(Stat::Greed)).round() as u32;
+            }
+            PickupKind::Freeze => {
+                for (_, _, _, mut effects) in &mut enemy_query {
+                    effects.apply(FREEZE);
+                }
+            }
+        }
+    }
+}
+
+pub struct PickupsPlugin;
+
+impl Plugin for PickupsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            collect_pickups
+                .before(gems::pickup_gems)
+                .in_set(SimulationSet),
+        );
+    }
+}
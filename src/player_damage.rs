@@ -0,0 +1,226 @@
+use bevy::prelude::*;
+
+use crate::{
+    simulation::{GameClock, SimulationSet},
+    stats::PlayerStats,
+    status_effects::{StatusEffect, StatusEffects},
+    Player,
+};
+
+// Seconds the player can't be hit again after taking damage.
+const INVINCIBILITY_TIME: f32 = 0.5;
+const BLINK_INTERVAL: f32 = 0.08;
+const BLINK_ALPHA: f32 = 0.3;
+
+/// Sent by anything that hurts the player, before armor and invincibility are applied.
+#[derive(Clone, Copy)]
+pub struct PlayerHitEvent {
+    pub damage: i32,
+    /// The enemy or projectile the hit came from.
+    pub source: Option<Entity>,
+    pub effect: Option<StatusEffect>,
+    /// Burn and poison ticks, they add up and land through invincibility and shields without
+    /// starting invincibility.
+    pub damage_over_time: bool,
+}
+
+/// Sent once a hit actually landed, `damage` is what was taken off the player's hp.
+pub struct PlayerDamagedEvent {
+    pub damage: i32,
+    pub source: Option<Entity>,
+}
+
+/// What the player takes this frame, systems in `PlayerDamageSet::Intercept` can change it.
+#[derive(Resource, Default)]
+pub struct IncomingHit {
+    /// The strongest hit of the frame. A hit with its damage set to 0 is blocked but still makes
+    /// the player invincible.
+    pub hit: Option<PlayerHitEvent>,
+    /// The effects of every hit that got through, they land along with `hit`.
+    pub effects: Vec<StatusEffect>,
+    /// Every damage over time tick of the frame added up.
+    pub damage_over_time: i32,
+}
+
+#[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PlayerDamageSet {
+    Receive,
+    /// On-hit passives that act before the hit lands, like shields.
+    Intercept,
+    Apply,
+}
+
+/// Seconds left before the player can take damage again.
+#[derive(Component, Default)]
+pub struct Invincibility {
+    remaining: f32,
+}
+
+/// Only the strongest hit of the frame deals damage but the effects of all of them are applied.
+/// Damage over time goes through invincibility, everything else is ignored while invincible.
+fn collect_hits<'a>(
+    hits: impl IntoIterator<Item = &'a PlayerHitEvent>,
+    invincible: bool,
+) -> IncomingHit {
+    let mut incoming = IncomingHit::default();
+    for hit in hits {
+        if hit.damage_over_time {
+            incoming.damage_over_time += hit.damage;
+            continue;
+        }
+        if invincible {
+            continue;
+        }
+        incoming.effects.extend(hit.effect);
+        if !matches!(incoming.hit, Some(strongest) if strongest.damage >= hit.damage) {
+            incoming.hit = Some(*hit);
+        }
+    }
+    incoming
+}
+
+fn receive_hits(
+    mut hit_reader: EventReader<PlayerHitEvent>,
+    player_query: Query<&Invincibility, With<Player>>,
+    mut incoming_hit: ResMut<IncomingHit>,
+) {
+    let Some(invincibility) = player_query.iter().next() else { return };
+    *incoming_hit = collect_hits(hit_reader.iter(), invincibility.remaining > 0.0);
+}
+
+fn apply_hit(
+    mut incoming_hit: ResMut<IncomingHit>,
+    mut player_query: Query<(
+        &mut Player,
+        &PlayerStats,
+        &mut Invincibility,
+        &mut StatusEffects,
+    )>,
+    mut damaged_writer: EventWriter<PlayerDamagedEvent>,
+) {
+    let incoming = std::mem::take(&mut *incoming_hit);
+    let Some((mut player, stats, mut invincibility, mut effects)) = player_query.iter_mut().next() else { return };
+
+    if incoming.damage_over_time > 0 {
+        let damage = stats.damage_taken(incoming.damage_over_time);
+        player.hp -= damage;
+        damaged_writer.send(PlayerDamagedEvent {
+            damage,
+            source: None,
+        });
+    }
+
+    let Some(hit) = incoming.hit else { return };
+    invincibility.remaining = INVINCIBILITY_TIME;
+    if hit.damage <= 0 {
+        return;
+    }
+    let damage = stats.damage_taken(hit.damage);
+    player.hp -= damage;
+    for effect in incoming.effects {
+        effects.apply(effect);
+    }
+    damaged_writer.send(PlayerDamagedEvent {
+        damage,
+        source: hit.source,
+    });
+}
+
+pub fn blink_invincible(
+    time: Res<GameClock>,
+    mut player_query: Query<(&mut Invincibility, &mut TextureAtlasSprite), With<Player>>,
+) {
+    let Some((mut invincibility, mut sprite)) = player_query.iter_mut().next() else { return };
+    if invincibility.remaining <= 0.0 {
+        return;
+    }
+    invincibility.remaining -= time.delta_seconds();
+
+    let blink_on = (invincibility.remaining / BLINK_INTERVAL) as i32 % 2 == 0;
+    let alpha = if blink_on && invincibility.remaining > 0.0 {
+        BLINK_ALPHA
+    } else {
+        1.0
+    };
+    sprite.color.set_a(alpha);
+}
+
+pub struct PlayerDamagePlugin;
+
+impl Plugin for PlayerDamagePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlayerHitEvent>()
+            .add_event::<PlayerDamagedEvent>()
+            .init_resource::<IncomingHit>()
+            .configure_sets(
+                (
+                    PlayerDamageSet::Receive,
+                    PlayerDamageSet::Intercept,
+                    PlayerDamageSet::Apply,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                (
+                    receive_hits.in_set(PlayerDamageSet::Receive),
+                    apply_hit.in_set(PlayerDamageSet::Apply),
+                    blink_invincible.after(PlayerDamageSet::Apply),
+                )
+                    .in_set(SimulationSet),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::status_effects::StatusKind;
+
+    const POISON: StatusEffect = StatusEffect {
+        kind: StatusKind::Poison,
+        strength: 2.0,
+        duration: 3.0,
+    };
+
+    fn hit(damage: i32, effect: Option<StatusEffect>) -> PlayerHitEvent {
+        PlayerHitEvent {
+            damage,
+            source: None,
+            effect,
+            damage_over_time: false,
+        }
+    }
+
+    fn tick(damage: i32) -> PlayerHitEvent {
+        PlayerHitEvent {
+            damage_over_time: true,
+            ..hit(damage, None)
+        }
+    }
+
+    #[test]
+    fn strongest_hit_deals_damage_and_every_effect_lands() {
+        let hits = [hit(5, Some(POISON)), hit(12, None), hit(8, None)];
+        let incoming = collect_hits(&hits, false);
+        assert_eq!(incoming.hit.map(|hit| hit.damage), Some(12));
+        assert_eq!(incoming.effects.len(), 1);
+        assert_eq!(incoming.damage_over_time, 0);
+    }
+
+    #[test]
+    fn damage_over_time_adds_up_next_to_a_hit() {
+        let hits = [tick(2), hit(10, None), tick(3)];
+        let incoming = collect_hits(&hits, false);
+        assert_eq!(incoming.hit.map(|hit| hit.damage), Some(10));
+        assert_eq!(incoming.damage_over_time, 5);
+    }
+
+    #[test]
+    fn only_damage_over_time_lands_while_invincible() {
+        let hits = [hit(10, Some(POISON)), tick(4)];
+        let incoming = collect_hits(&hits, true);
+        assert!(incoming.hit.is_none());
+        assert!(incoming.effects.is_empty());
+        assert_eq!(incoming.damage_over_time, 4);
+    }
+}
@@ -0,0 +1,155 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+use std::f32::consts::TAU;
+
+use crate::{
+    physics_groups,
+    pickups::{roll_drop, PickupDrop, PickupKind},
+    simulation::{GameClock, SimulationSet},
+    stats::{PlayerStats, Stat},
+    weapon::Attack,
+    Player, WINDOW_SIZE,
+};
+
+const MAX_PROPS: usize = 8;
+const PROP_SPAWN_INTERVAL: f32 = 6.0;
+const PROP_SIZE: Vec2 = Vec2::new(10.0, 16.0);
+const PROP_COLOR: Color = Color::rgb(1.0, 0.6, 0.2);
+// Props this far from the player are removed to make room for new ones.
+const PROP_DESPAWN_DISTANCE: f32 = WINDOW_SIZE * 1.5;
+
+/// Props always drop something.
+const PROP_DROPS: [PickupDrop; 5] = [
+    PickupDrop {
+        kind: PickupKind::Gold,
+        chance: 0.45,
+    },
+    PickupDrop {
+        kind: PickupKind::Heal,
+        chance: 0.3,
+    },
+    PickupDrop {
+        kind: PickupKind::Bomb,
+        chance: 0.1,
+    },
+    PickupDrop {
+        kind: PickupKind::Freeze,
+        chance: 0.1,
+    },
+    PickupDrop {
+        kind: PickupKind::Vacuum,
+        chance: 0.05,
+    },
+];
+
+/// Light sources scattered around the map that break when hit by an attack.
+#[derive(Component, Default)]
+pub struct Prop {
+    /// Set by things other than attacks that break props, like bombs.
+    pub broken: bool,
+}
+
+#[derive(Resource, Deref, DerefMut)]
+struct PropSpawnTimer(Timer);
+
+fn spawn_props(
+    mut commands: Commands,
+    time: Res<GameClock>,
+    mut spawn_timer: ResMut<PropSpawnTimer>,
+    player_query: Query<&Transform, With<Player>>,
+    prop_query: Query<(), With<Prop>>,
+) {
+    spawn_timer.tick(time.delta());
+    if !spawn_timer.just_finished() || prop_query.iter().len() >= MAX_PROPS {
+        return;
+    }
+    let Some(player_transform) = player_query.iter().next() else { return };
+
+    // Just outside the screen so props don't pop in under the player.
+    let mut rng = rand::thread_rng();
+    let offset = Vec2::from_angle(rng.gen_range(0.0..TAU)) * WINDOW_SIZE * 0.8;
+    let position = player_transform.translation.truncate() + offset;
+    commands.spawn((
+        Prop::default(),
+        SpriteBundle {
+            sprite: Sprite {
+                color: PROP_COLOR,
+                custom_size: Some(PROP_SIZE),
+                ..default()
+            },
+            transform: Transform::from_translation(position.extend(0.5)),
+            ..default()
+        },
+        RigidBody::Fixed,
+        Sensor,
+        Collider::cuboid(PROP_SIZE.x / 2.0, PROP_SIZE.y / 2.0),
+        CollisionGroups::new(physics_groups::PROP_GROUP, physics_groups::ATTACK_GROUP),
+    ));
+}
+
+fn break_props(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    rapier_context: Res<RapierContext>,
+    player_query: Query<&PlayerStats>,
+    prop_query: Query<(Entity, &Prop, &Transform)>,
+    attack_query: Query<(), With<Attack>>,
+) {
+    let luck = player_query
+        .iter()
+        .next()
+        .map_or(1.0, |stats| stats.get(Stat::Luck));
+    let mut rng = rand::thread_rng();
+
+    for (prop_entity, prop, transform) in &prop_query {
+        let hit = prop.broken
+            || rapier_context.intersections_with(prop_entity).any(
+                |(collider1, collider2, intersecting)| {
+                    let other = if collider1 == prop_entity {
+                        collider2
+                    } else {
+                        collider1
+                    };
+                    intersecting && attack_query.contains(other)
+                },
+            );
+        if !hit {
+            continue;
+        }
+        commands.entity(prop_entity).despawn();
+        roll_drop(
+            &mut commands,
+            &asset_server,
+            transform.translation,
+            &PROP_DROPS,
+            luck,
+            &mut rng,
+        );
+    }
+}
+
+fn despawn_far_props(
+    mut commands: Commands,
+    player_query: Query<&Transform, With<Player>>,
+    prop_query: Query<(Entity, &Transform), With<Prop>>,
+) {
+    let Some(player_transform) = player_query.iter().next() else { return };
+    for (prop_entity, transform) in &prop_query {
+        if transform.translation.distance(player_transform.translation) > PROP_DESPAWN_DISTANCE {
+            commands.entity(prop_entity).despawn();
+        }
+    }
+}
+
+pub struct PropsPlugin;
+
+impl Plugin for PropsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PropSpawnTimer(Timer::from_seconds(
+            PROP_SPAWN_INTERVAL,
+            TimerMode::Repeating,
+        )))
+        .add_systems((spawn_props, break_props, despawn_far_props).in_set(SimulationSet));
+    }
+}
@@ -0,0 +1,126 @@
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowMode};
+use serde::{Deserialize, Serialize};
+
+use crate::{actions::KeyBindings, WINDOW_SIZE};
+
+#[cfg(not(target_arch = "wasm32"))]
+const SETTINGS_FILE: &str = "settings.ron";
+#[cfg(target_arch = "wasm32")]
+const SETTINGS_KEY: &str = "billions_must_die.settings";
+
+/// Everything the options screen changes, saved between sessions.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// 0 to 1, scales the volume the music was mixed at.
+    pub music_volume: f32,
+    /// 0 to 1, scales the volume the sound effects were mixed at.
+    pub sfx_volume: f32,
+    pub fullscreen: bool,
+    /// Window size as a multiple of `WINDOW_SIZE`.
+    pub window_scale: f32,
+    pub damage_numbers: bool,
+    /// Adds rapid hits on the same enemy into one number instead of spawning one per hit.
+    pub aggregate_damage_numbers: bool,
+    /// Multiplier for the camera shake, 0 turns it off.
+    pub screen_shake: f32,
+    pub key_bindings: KeyBindings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+            fullscreen: false,
+            window_scale: 1.0,
+            damage_numbers: true,
+            aggregate_damage_numbers: false,
+            screen_shake: 1.0,
+            key_bindings: KeyBindings::default(),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_settings() -> Option<String> {
+    std::fs::read_to_string(SETTINGS_FILE).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_settings(contents: &str) {
+    if let Err(err) = std::fs::write(SETTINGS_FILE, contents) {
+        warn!("Couldn't save settings: {}", err);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_settings() -> Option<String> {
+    local_storage()?.get_item(SETTINGS_KEY).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_settings(contents: &str) {
+    let Some(storage) = local_storage() else { return };
+    if storage.set_item(SETTINGS_KEY, contents).is_err() {
+        warn!("Couldn't save settings to local storage");
+    }
+}
+
+/// Missing or unreadable settings fall back to the defaults.
+fn load_settings() -> Settings {
+    let Some(contents) = read_settings() else { return Settings::default() };
+    ron::from_str(&contents).unwrap_or_else(|err| {
+        warn!("Couldn't read settings, using the defaults: {}", err);
+        Settings::default()
+    })
+}
+
+/// Saves every change right away, so rebinds and changes made before quitting aren't lost.
+fn save_settings(settings: Res<Settings>) {
+    if !settings.is_changed() || settings.is_added() {
+        return;
+    }
+    match ron::ser::to_string_pretty(&*settings, ron::ser::PrettyConfig::default()) {
+        Ok(contents) => write_settings(&contents),
+        Err(err) => warn!("Couldn't save settings: {}", err),
+    }
+}
+
+fn apply_window_settings(
+    settings: Res<Settings>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    let Some(mut window) = window_query.iter_mut().next() else { return };
+    let mode = if settings.fullscreen {
+        WindowMode::BorderlessFullscreen
+    } else {
+        WindowMode::Windowed
+    };
+    if window.mode != mode {
+        window.mode = mode;
+    }
+    let size = WINDOW_SIZE * settings.window_scale;
+    if window.resolution.width() != size || window.resolution.height() != size {
+        window.resolution.set(size, size);
+    }
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_settings())
+            .add_system(apply_window_settings)
+            .add_system(save_settings);
+    }
+}
@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::{player_damage::PlayerDamagedEvent, settings::Settings, GameState};
+
+// Sound effects are mixed as loud as the music, this is their volume at 100% in the options.
+const SFX_VOLUME: f32 = 0.08;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sfx {
+    PlayerHurt,
+    LevelUp,
+    ChestOpen,
+}
+
+const SFX: [(Sfx, &str); 3] = [
+    (Sfx::PlayerHurt, "sfx/player_hurt.ogg"),
+    (Sfx::LevelUp, "sfx/level_up.ogg"),
+    (Sfx::ChestOpen, "sfx/chest_open.ogg"),
+];
+
+/// Plays a sound effect at the volume from the settings.
+pub struct SfxEvent(pub Sfx);
+
+#[derive(Resource, Default)]
+struct SfxHandles(HashMap<Sfx, Handle<AudioSource>>);
+
+fn load_sfx(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let mut handles = SfxHandles::default();
+    for (sfx, path) in SFX {
+        handles.0.insert(sfx, asset_server.load(path));
+    }
+    commands.insert_resource(handles);
+}
+
+/// Sounds that aren't loaded are skipped, the audio plugin would otherwise keep them queued.
+fn play_sfx(
+    mut sfx_reader: EventReader<SfxEvent>,
+    settings: Res<Settings>,
+    handles: Res<SfxHandles>,
+    audio: Res<Audio>,
+    audio_sources: Res<Assets<AudioSource>>,
+) {
+    for SfxEvent(sfx) in sfx_reader.iter() {
+        let Some(handle) = handles.0.get(sfx) else { continue };
+        if settings.sfx_volume <= 0.0 || audio_sources.get(handle).is_none() {
+            continue;
+        }
+        audio.play_with_settings(
+            handle.clone(),
+            PlaybackSettings {
+                volume: SFX_VOLUME * settings.sfx_volume,
+                ..default()
+            },
+        );
+    }
+}
+
+fn hurt_sfx(
+    mut damaged_reader: EventReader<PlayerDamagedEvent>,
+    mut sfx_writer: EventWriter<SfxEvent>,
+) {
+    // Damage over time has no source, it would play every tick.
+    if damaged_reader
+        .iter()
+        .any(|damaged| damaged.source.is_some())
+    {
+        sfx_writer.send(SfxEvent(Sfx::PlayerHurt));
+    }
+}
+
+fn level_up_sfx(mut sfx_writer: EventWriter<SfxEvent>) {
+    sfx_writer.send(SfxEvent(Sfx::LevelUp));
+}
+
+fn chest_open_sfx(mut sfx_writer: EventWriter<SfxEvent>) {
+    sfx_writer.send(SfxEvent(Sfx::ChestOpen));
+}
+
+pub struct SfxPlugin;
+
+impl Plugin for SfxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SfxEvent>()
+            .add_startup_system(load_sfx)
+            .add_system(hurt_sfx)
+            .add_system(play_sfx.after(hurt_sfx))
+            .add_system(level_up_sfx.in_schedule(OnEnter(GameState::LevellingUp)))
+            .add_system(chest_open_sfx.in_schedule(OnEnter(GameState::OpeningChest)));
+    }
+}
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use std::time::Duration;
+
+use crate::GameState;
+
+/// Gameplay systems, they only run while the simulation is running.
+#[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SimulationSet;
+
+/// Game time, gameplay systems read it instead of `Time` so it stops while the game is paused or
+/// in a menu and can be slowed down or sped up.
+#[derive(Resource)]
+pub struct GameClock {
+    delta: Duration,
+    pub time_scale: f32,
+}
+
+impl Default for GameClock {
+    fn default() -> Self {
+        Self {
+            delta: Duration::ZERO,
+            time_scale: 1.0,
+        }
+    }
+}
+
+impl GameClock {
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+}
+
+/// The only place deciding whether the simulation runs, everything else follows the game state.
+pub fn simulation_running(state: Res<State<GameState>>) -> bool {
+    state.0 == GameState::Playing
+}
+
+fn tick_game_clock(
+    time: Res<Time>,
+    state: Res<State<GameState>>,
+    mut game_clock: ResMut<GameClock>,
+) {
+    let time_scale = game_clock.time_scale.max(0.0);
+    game_clock.delta = if simulation_running(state) {
+        time.delta().mul_f32(time_scale)
+    } else {
+        Duration::ZERO
+    };
+}
+
+/// Physics stops and scales with the game clock.
+fn sync_physics(
+    state: Res<State<GameState>>,
+    game_clock: Res<GameClock>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    let active = simulation_running(state) && game_clock.time_scale > 0.0;
+    if rapier_config.physics_pipeline_active != active {
+        rapier_config.physics_pipeline_active = active;
+    }
+    if let TimestepMode::Variable { time_scale, .. } = &mut rapier_config.timestep_mode {
+        if *time_scale != game_clock.time_scale {
+            *time_scale = game_clock.time_scale;
+        }
+    }
+}
+
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameClock>()
+            .configure_set(SimulationSet.run_if(simulation_running))
+            // Right after the state changes so nothing runs for a frame in the wrong state.
+            .add_systems(
+                (tick_game_clock, sync_physics)
+                    .after(apply_state_transition::<GameState>)
+                    .in_base_set(CoreSet::StateTransitions),
+            );
+    }
+}
@@ -0,0 +1,333 @@
+use bevy::asset::{AssetLoader, LoadContext, LoadedAsset};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::utils::BoxedFuture;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::Deserialize;
+use std::f32::consts::TAU;
+
+use crate::{
+    boss::make_boss,
+    enemies::{make_elite, spawn_enemy, Enemy, EnemyAtlases, EnemyKind},
+    simulation::{GameClock, SimulationSet},
+    Player, WINDOW_SIZE,
+};
+
+const TIMELINE_PATH: &str = "spawns.timeline.ron";
+// Gap between enemies in a line formation.
+const LINE_SPACING: f32 = 24.0;
+
+#[derive(Deserialize)]
+struct WeightedEnemy {
+    enemy: EnemyKind,
+    weight: f32,
+}
+
+/// The trickle of enemies from `start` seconds into the run until the next phase starts.
+#[derive(Deserialize)]
+struct SpawnPhase {
+    start: f32,
+    spawn_interval: f32,
+    max_alive: usize,
+    enemies: Vec<WeightedEnemy>,
+    /// Chance for each spawned enemy to be an elite.
+    #[serde(default)]
+    elite_chance: f32,
+}
+
+#[derive(Deserialize)]
+enum SpawnEventKind {
+    /// Surrounds the player with enemies.
+    Ring { enemy: EnemyKind, count: usize },
+    /// A wall of enemies coming from one side.
+    Line { enemy: EnemyKind, count: usize },
+    /// A tougher enemy that drops a chest.
+    Elite { enemy: EnemyKind },
+    /// A single scaled-up enemy with its own attacks and hp bar.
+    Boss {
+        enemy: EnemyKind,
+        name: String,
+        hp: i32,
+    },
+}
+
+#[derive(Deserialize)]
+struct SpawnEvent {
+    time: f32,
+    kind: SpawnEventKind,
+}
+
+#[derive(Deserialize, TypeUuid)]
+#[uuid = "4f0c7d2e-5b1a-4a8e-9d36-2f7c1e8b9a40"]
+pub struct SpawnTimeline {
+    phases: Vec<SpawnPhase>,
+    events: Vec<SpawnEvent>,
+}
+
+impl SpawnTimeline {
+    /// Rejects values the director can't run with, so a typo in the file fails the load instead of
+    /// crashing the game mid-run.
+    fn validate(&self) -> Result<(), String> {
+        for phase in &self.phases {
+            let at = phase.start;
+            if phase.spawn_interval.is_nan() || phase.spawn_interval <= 0.0 {
+                return Err(format!("phase at {at}s: spawn_interval must be above 0"));
+            }
+            if !(0.0..=1.0).contains(&phase.elite_chance) {
+                return Err(format!(
+                    "phase at {at}s: elite_chance must be between 0 and 1"
+                ));
+            }
+            if phase
+                .enemies
+                .iter()
+                .any(|enemy| enemy.weight.is_nan() || enemy.weight < 0.0)
+            {
+                return Err(format!("phase at {at}s: enemy weights can't be negative"));
+            }
+            if !phase.enemies.iter().any(|enemy| enemy.weight > 0.0) {
+                return Err(format!(
+                    "phase at {at}s: needs an enemy with a weight above 0"
+                ));
+            }
+        }
+        for event in &self.events {
+            if let SpawnEventKind::Boss { hp, .. } = event.kind {
+                if hp <= 0 {
+                    return Err(format!("event at {}s: boss hp must be above 0", event.time));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Phases and events come out sorted by time, whatever order the file lists them in.
+fn parse_timeline(bytes: &[u8]) -> Result<SpawnTimeline, bevy::asset::Error> {
+    let mut timeline = ron::de::from_bytes::<SpawnTimeline>(bytes)?;
+    timeline.validate().map_err(bevy::asset::Error::msg)?;
+    timeline.phases.sort_by(|a, b| a.start.total_cmp(&b.start));
+    timeline.events.sort_by(|a, b| a.time.total_cmp(&b.time));
+    Ok(timeline)
+}
+
+#[derive(Default)]
+struct SpawnTimelineLoader;
+
+impl AssetLoader for SpawnTimelineLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let timeline = parse_timeline(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(timeline));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["timeline.ron"]
+    }
+}
+
+#[derive(Resource)]
+struct SpawnTimelineHandle(Handle<SpawnTimeline>);
+
+#[derive(Component)]
+pub struct EnemySpawner {
+    elapsed: f32,
+    timer: Timer,
+    next_event: usize,
+}
+
+fn load_timeline(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(SpawnTimelineHandle(asset_server.load(TIMELINE_PATH)));
+}
+
+pub fn setup_spawns(mut commands: Commands) {
+    commands.spawn(EnemySpawner {
+        elapsed: 0.0,
+        timer: Timer::from_seconds(0.0, TimerMode::Repeating),
+        next_event: 0,
+    });
+}
+
+fn spawn_radius() -> f32 {
+    let distance_from_center: f32 = WINDOW_SIZE / 2.0;
+    (distance_from_center.powf(2.0) + distance_from_center.powf(2.0)).sqrt() + 10.0
+}
+
+fn run_event(
+    commands: &mut Commands,
+    enemy_atlases: &EnemyAtlases,
+    event: &SpawnEvent,
+    player_position: Vec2,
+) {
+    let mut rng = rand::thread_rng();
+    let radius = spawn_radius();
+    match &event.kind {
+        &SpawnEventKind::Ring { enemy, count } => {
+            for index in 0..count {
+                let point_on_circle = Vec2::from_angle(index as f32 * TAU / count as f32);
+                let position = player_position + point_on_circle * radius;
+                spawn_enemy(commands, enemy_atlases, enemy, position.extend(1.0));
+            }
+        }
+        &SpawnEventKind::Line { enemy, count } => {
+            let direction = Vec2::from_angle(rng.gen_range(0.0..TAU));
+            let line_center = player_position + direction * radius;
+            let first_offset = -LINE_SPACING * (count as f32 - 1.0) / 2.0;
+            for index in 0..count {
+                let offset = direction.perp() * (first_offset + LINE_SPACING * index as f32);
+                let position = line_center + offset;
+                spawn_enemy(commands, enemy_atlases, enemy, position.extend(1.0));
+            }
+        }
+        &SpawnEventKind::Elite { enemy } => {
+            let point_on_circle = Vec2::from_angle(rng.gen_range(0.0..TAU));
+            let position = player_position + point_on_circle * radius;
+            let elite_entity = spawn_enemy(commands, enemy_atlases, enemy, position.extend(1.0));
+            make_elite(commands, elite_entity, enemy);
+        }
+        SpawnEventKind::Boss { enemy, name, hp } => {
+            let point_on_circle = Vec2::from_angle(rng.gen_range(0.0..TAU));
+            let position = player_position + point_on_circle * radius;
+            let boss_entity = spawn_enemy(commands, enemy_atlases, *enemy, position.extend(1.0));
+            make_boss(commands, boss_entity, *enemy, name, *hp);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn direct_spawns(
+    mut commands: Commands,
+    enemy_atlases: Res<EnemyAtlases>,
+    time: Res<GameClock>,
+    timeline_handle: Res<SpawnTimelineHandle>,
+    timelines: Res<Assets<SpawnTimeline>>,
+    mut enemy_spawner_query: Query<&mut EnemySpawner>,
+    player_transform_query: Query<&Transform, With<Player>>,
+    enemy_query: Query<(), With<Enemy>>,
+) {
+    let Some(timeline) = timelines.get(&timeline_handle.0) else { return };
+    let Some(player_transform) = player_transform_query.iter().next() else { return };
+    let player_position = player_transform.translation.truncate();
+    let mut rng = rand::thread_rng();
+
+    for mut enemy_spawner in &mut enemy_spawner_query {
+        enemy_spawner.elapsed += time.delta_seconds();
+        let elapsed = enemy_spawner.elapsed;
+
+        while let Some(event) = timeline.events.get(enemy_spawner.next_event) {
+            if event.time > elapsed {
+                break;
+            }
+            run_event(&mut commands, &enemy_atlases, event, player_position);
+            enemy_spawner.next_event += 1;
+        }
+
+        let Some(phase) = timeline
+            .phases
+            .iter()
+            .rev()
+            .find(|phase| phase.start <= elapsed)
+        else {
+            continue;
+        };
+        let spawn_interval = std::time::Duration::from_secs_f32(phase.spawn_interval);
+        if enemy_spawner.timer.duration() != spawn_interval {
+            enemy_spawner.timer.set_duration(spawn_interval);
+        }
+        enemy_spawner.timer.tick(time.delta());
+        if !enemy_spawner.timer.just_finished() || enemy_query.iter().count() >= phase.max_alive {
+            continue;
+        }
+        let Ok(weighted_enemy) = phase
+            .enemies
+            .choose_weighted(&mut rng, |weighted_enemy| weighted_enemy.weight)
+        else {
+            continue;
+        };
+        let point_on_circle = Vec2::from_angle(rng.gen_range(0.0..TAU));
+        let position = player_position + point_on_circle * spawn_radius();
+        let enemy_entity = spawn_enemy(
+            &mut commands,
+            &enemy_atlases,
+            weighted_enemy.enemy,
+            position.extend(1.0),
+        );
+        if rng.gen::<f32>() < phase.elite_chance {
+            make_elite(&mut commands, enemy_entity, weighted_enemy.enemy);
+        }
+    }
+}
+
+pub struct SpawnDirectorPlugin;
+
+impl Plugin for SpawnDirectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<SpawnTimeline>()
+            .init_asset_loader::<SpawnTimelineLoader>()
+            .add_startup_system(load_timeline)
+            .add_system(direct_spawns.in_set(SimulationSet));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timeline(phases: &str, events: &str) -> Result<SpawnTimeline, bevy::asset::Error> {
+        parse_timeline(format!("(phases: [{phases}], events: [{events}])").as_bytes())
+    }
+
+    const PHASE: &str = "(start: 0.0, spawn_interval: 0.4, max_alive: 60, \
+        enemies: [(enemy: Soyjak, weight: 1.0)])";
+
+    #[test]
+    fn phases_and_events_are_sorted_by_time() {
+        let timeline = timeline(
+            &format!("{}, {PHASE}", PHASE.replace("start: 0.0", "start: 60.0")),
+            "(time: 90.0, kind: Elite(enemy: Soyjak)), (time: 45.0, kind: Ring(enemy: Bat, count: 4))",
+        )
+        .unwrap();
+        let starts: Vec<f32> = timeline.phases.iter().map(|phase| phase.start).collect();
+        let times: Vec<f32> = timeline.events.iter().map(|event| event.time).collect();
+        assert_eq!(starts, [0.0, 60.0]);
+        assert_eq!(times, [45.0, 90.0]);
+        assert_eq!(timeline.phases[0].elite_chance, 0.0);
+    }
+
+    #[test]
+    fn the_shipped_timeline_loads() {
+        let bytes = std::fs::read(format!(
+            "{}/assets/{TIMELINE_PATH}",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        assert!(parse_timeline(&bytes).is_ok());
+    }
+
+    #[test]
+    fn invalid_phases_are_rejected() {
+        for invalid in [
+            PHASE.replace("spawn_interval: 0.4", "spawn_interval: 0.0"),
+            PHASE.replace("spawn_interval: 0.4", "spawn_interval: -1.0"),
+            PHASE.replace("max_alive: 60", "max_alive: -5"),
+            PHASE.replace("weight: 1.0", "weight: -1.0"),
+            PHASE.replace("weight: 1.0", "weight: 0.0"),
+            PHASE.replace("max_alive: 60", "max_alive: 60, elite_chance: 2.0"),
+        ] {
+            assert!(timeline(&invalid, "").is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn bosses_need_hp() {
+        let boss = "(time: 10.0, kind: Boss(enemy: Cobson, name: \"Boss\", hp: 0))";
+        assert!(timeline(PHASE, boss).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::{
+    simulation::{GameClock, SimulationSet},
+    Player,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Stat {
+    /// Damage multiplier for every weapon.
+    Might,
+    /// Size multiplier for projectiles.
+    Area,
+    /// Weapon cooldown multiplier, lower is faster.
+    Cooldown,
+    /// Extra projectiles fired by every weapon.
+    Amount,
+    ProjectileSpeed,
+    /// How long projectiles stay out.
+    Duration,
+    /// Flat damage reduction for every hit taken.
+    Armor,
+    /// HP recovered per second.
+    Regen,
+    MoveSpeed,
+    /// Radius in which gems are pulled towards the player.
+    Magnet,
+    Luck,
+    /// Experience multiplier.
+    Growth,
+    /// Gold multiplier.
+    Greed,
+    /// Damage dealt back to enemies that touch the player.
+    Thorns,
+    /// Hits that can be blocked before the shield has to recharge.
+    Shield,
+}
+
+pub const STATS: [Stat; 15] = [
+    Stat::Might,
+    Stat::Area,
+    Stat::Cooldown,
+    Stat::Amount,
+    Stat::ProjectileSpeed,
+    Stat::Duration,
+    Stat::Armor,
+    Stat::Regen,
+    Stat::MoveSpeed,
+    Stat::Magnet,
+    Stat::Luck,
+    Stat::Growth,
+    Stat::Greed,
+    Stat::Thorns,
+    Stat::Shield,
+];
+
+impl Stat {
+    pub fn base_value(self) -> f32 {
+        match self {
+            Stat::Amount | Stat::Armor | Stat::Regen | Stat::Thorns | Stat::Shield => 0.0,
+            Stat::MoveSpeed => 100.0,
+            Stat::Magnet => 40.0,
+            _ => 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum StatModifier {
+    Add(Stat, f32),
+    Multiply(Stat, f32),
+}
+
+/// Every stat is `(base + sum of additions) * product of multipliers`.
+#[derive(Component)]
+pub struct PlayerStats {
+    base: HashMap<Stat, f32>,
+    modifiers: Vec<StatModifier>,
+}
+
+impl Default for PlayerStats {
+    fn default() -> Self {
+        Self {
+            base: STATS
+                .iter()
+                .map(|stat| (*stat, stat.base_value()))
+                .collect(),
+            modifiers: Vec::new(),
+        }
+    }
+}
+
+impl PlayerStats {
+    /// Default stats with some base values replaced, used by characters.
+    pub fn with_base_values(base_values: &[(Stat, f32)]) -> Self {
+        let mut stats = Self::default();
+        stats.base.extend(base_values.iter().copied());
+        stats
+    }
+
+    pub fn get(&self, stat: Stat) -> f32 {
+        let mut added = 0.0;
+        let mut multiplier = 1.0;
+        for modifier in self.modifiers.iter() {
+            match *modifier {
+                StatModifier::Add(modified, value) if modified == stat => added += value,
+                StatModifier::Multiply(modified, value) if modified == stat => multiplier *= value,
+                _ => {}
+            }
+        }
+        (self.base[&stat] + added) * multiplier
+    }
+
+    pub fn add_modifier(&mut self, modifier: StatModifier) {
+        self.modifiers.push(modifier);
+    }
+
+    /// Reduces incoming damage by armor, every hit still does at least 1 damage.
+    pub fn damage_taken(&self, damage: i32) -> i32 {
+        (damage - self.get(Stat::Armor) as i32).max(1)
+    }
+}
+
+/// Hp regenerated that doesn't add up to a whole point yet.
+#[derive(Resource, Default)]
+pub struct Regenerated(f32);
+
+fn regenerate(
+    time: Res<GameClock>,
+    mut player_query: Query<(&mut Player, &PlayerStats)>,
+    mut regenerated: ResMut<Regenerated>,
+) {
+    let Some((mut player, stats)) = player_query.iter_mut().next() else { return };
+    regenerated.0 += stats.get(Stat::Regen) * time.delta_seconds();
+    let whole_hp = regenerated.0.floor();
+    if whole_hp >= 1.0 {
+        regenerated.0 -= whole_hp;
+        player.hp = (player.hp + whole_hp as i32).min(player.max_hp);
+    }
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Regenerated>()
+            .add_system(regenerate.in_set(SimulationSet));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn additions_apply_before_multipliers() {
+        let mut stats = PlayerStats::with_base_values(&[(Stat::Might, 2.0)]);
+        stats.add_modifier(StatModifier::Multiply(Stat::Might, 1.5));
+        stats.add_modifier(StatModifier::Add(Stat::Might, 1.0));
+        stats.add_modifier(StatModifier::Multiply(Stat::Might, 2.0));
+        stats.add_modifier(StatModifier::Add(Stat::Area, 5.0));
+        assert_eq!(stats.get(Stat::Might), 9.0);
+        assert_eq!(stats.get(Stat::Area), 6.0);
+        assert_eq!(stats.get(Stat::MoveSpeed), 100.0);
+    }
+
+    #[test]
+    fn armor_reduces_damage_down_to_1() {
+        let mut stats = PlayerStats::default();
+        assert_eq!(stats.damage_taken(10), 10);
+        stats.add_modifier(StatModifier::Add(Stat::Armor, 3.0));
+        assert_eq!(stats.damage_taken(10), 7);
+        assert_eq!(stats.damage_taken(2), 1);
+    }
+}
@@ -0,0 +1,235 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::{
+    effects::{DamageNumberEvent, DamageType},
+    enemies::Enemy,
+    enemy_movement::{self, EnemyMovement},
+    game_over::RunStats,
+    player_damage::{self, PlayerDamageSet, PlayerHitEvent},
+    simulation::{GameClock, SimulationSet},
+    Player,
+};
+
+const MAX_POISON_STACKS: u32 = 5;
+// Damage over time is dealt in chunks so the damage numbers stay readable.
+const DOT_INTERVAL: f32 = 0.5;
+const KNOCKBACK_TIME: f32 = 0.15;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StatusKind {
+    /// Damage over time, reapplying keeps the strongest burn.
+    Burn,
+    /// Movement speed multiplier, reapplying keeps the strongest slow.
+    Slow,
+    /// Can't move or attack.
+    Freeze,
+    /// Damage over time for every stack, each application adds a stack.
+    Poison,
+}
+
+/// Sprite tints from the highest priority down, the first active status wins.
+const STATUS_TINTS: [(StatusKind, Color); 4] = [
+    (StatusKind::Freeze, Color::rgb(0.5, 0.7, 1.0)),
+    (StatusKind::Burn, Color::rgb(1.0, 0.5, 0.2)),
+    (StatusKind::Poison, Color::rgb(0.5, 1.0, 0.3)),
+    (StatusKind::Slow, Color::rgb(0.7, 0.5, 1.0)),
+];
+
+#[derive(Clone, Copy, Debug)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    /// Damage per second for burn and poison, speed multiplier for slow, unused for freeze.
+    pub strength: f32,
+    pub duration: f32,
+}
+
+struct ActiveStatus {
+    strength: f32,
+    stacks: u32,
+    remaining: f32,
+}
+
+/// Timed effects on an enemy or the player, every application refreshes the duration.
+#[derive(Component, Default)]
+pub struct StatusEffects {
+    active: HashMap<StatusKind, ActiveStatus>,
+    /// Velocity forced on the entity and the seconds left.
+    knockback: Option<(Vec2, f32)>,
+    dot_timer: f32,
+    tinted: bool,
+}
+
+impl StatusEffects {
+    pub fn apply(&mut self, effect: StatusEffect) {
+        let status = self.active.entry(effect.kind).or_insert(ActiveStatus {
+            strength: effect.strength,
+            stacks: 0,
+            remaining: 0.0,
+        });
+        status.remaining = status.remaining.max(effect.duration);
+        status.stacks = (status.stacks + 1).min(match effect.kind {
+            StatusKind::Poison => MAX_POISON_STACKS,
+            _ => 1,
+        });
+        status.strength = match effect.kind {
+            StatusKind::Slow => status.strength.min(effect.strength),
+            _ => status.strength.max(effect.strength),
+        };
+    }
+
+    pub fn knock_back(&mut self, velocity: Vec2) {
+        self.knockback = Some((velocity, KNOCKBACK_TIME));
+    }
+
+    pub fn knockback_velocity(&self) -> Option<Vec2> {
+        self.knockback.map(|(velocity, _)| velocity)
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.active.contains_key(&StatusKind::Freeze)
+    }
+
+    /// 0 while frozen, the slow multiplier while slowed.
+    pub fn speed_multiplier(&self) -> f32 {
+        if self.is_frozen() {
+            return 0.0;
+        }
+        match self.active.get(&StatusKind::Slow) {
+            Some(slow) => slow.strength,
+            None => 1.0,
+        }
+    }
+
+    /// Damage per second of every active damage over time effect.
+    fn damage_over_time(&self) -> Vec<(DamageType, f32)> {
+        self.active
+            .iter()
+            .filter_map(|(kind, status)| {
+                let damage_type = match kind {
+                    StatusKind::Burn => DamageType::Fire,
+                    StatusKind::Poison => DamageType::Poison,
+                    _ => return None,
+                };
+                Some((damage_type, status.strength * status.stacks as f32))
+            })
+            .collect()
+    }
+}
+
+type AfflictedEntity<'a> = (
+    Entity,
+    &'a mut StatusEffects,
+    &'a Transform,
+    Option<&'a mut Enemy>,
+    Option<&'a Player>,
+);
+
+fn tick_status_effects(
+    time: Res<GameClock>,
+    mut status_query: Query<AfflictedEntity>,
+    mut damage_number_writer: EventWriter<DamageNumberEvent>,
+    mut hit_writer: EventWriter<PlayerHitEvent>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut effects, transform, mut enemy, player) in &mut status_query {
+        if effects.active.is_empty() && effects.knockback.is_none() {
+            continue;
+        }
+        let damage_over_time = effects.damage_over_time();
+        effects.active.retain(|_, status| {
+            status.remaining -= delta;
+            status.remaining > 0.0
+        });
+        if let Some((_, remaining)) = effects.knockback.as_mut() {
+            *remaining -= delta;
+            if *remaining <= 0.0 {
+                effects.knockback = None;
+            }
+        }
+
+        if damage_over_time.is_empty() {
+            effects.dot_timer = 0.0;
+            continue;
+        }
+        effects.dot_timer += delta;
+        if effects.dot_timer < DOT_INTERVAL {
+            continue;
+        }
+        effects.dot_timer -= DOT_INTERVAL;
+
+        for (damage_type, damage_per_second) in damage_over_time {
+            let damage = ((damage_per_second * DOT_INTERVAL).round() as i32).max(1);
+            if let Some(enemy) = enemy.as_mut() {
+                if enemy.hp <= 0 {
+                    break;
+                }
+                enemy.hp -= damage;
+                run_stats.damage_dealt += damage;
+                damage_number_writer.send(DamageNumberEvent {
+                    dmg: damage,
+                    position: transform.translation,
+                    crit: false,
+                    damage_type,
+                    target: entity,
+                });
+            }
+            if player.is_some() {
+                hit_writer.send(PlayerHitEvent {
+                    damage,
+                    source: None,
+                    effect: None,
+                    damage_over_time: true,
+                });
+            }
+        }
+    }
+}
+
+/// Only tints the color, the alpha is left to the invincibility blink.
+fn tint_status_effects(
+    mut status_query: Query<(
+        &mut StatusEffects,
+        &mut TextureAtlasSprite,
+        Option<&EnemyMovement>,
+    )>,
+) {
+    for (mut effects, mut sprite, enemy_movement) in &mut status_query {
+        let tint = STATUS_TINTS
+            .iter()
+            .find(|(kind, _)| effects.active.contains_key(kind));
+        match tint {
+            Some((_, color)) => {
+                sprite.color = color.with_a(sprite.color.a());
+                effects.tinted = true;
+            }
+            None if effects.tinted => {
+                let color = match enemy_movement {
+                    Some(enemy_movement) => enemy_movement.color(),
+                    None => Color::WHITE,
+                };
+                sprite.color = color.with_a(sprite.color.a());
+                effects.tinted = false;
+            }
+            None => {}
+        }
+    }
+}
+
+pub struct StatusEffectsPlugin;
+
+impl Plugin for StatusEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (
+                tick_status_effects.before(PlayerDamageSet::Receive),
+                // The dash telegraph and invincibility blink draw over the tint.
+                tint_status_effects
+                    .before(enemy_movement::move_enemies)
+                    .before(player_damage::blink_invincible),
+            )
+                .in_set(SimulationSet),
+        );
+    }
+}
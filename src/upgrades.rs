@@ -0,0 +1,266 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::{
+    passive_items::{
+        PassiveItem, PassiveKind, UpgradePassiveEvent, MAX_PASSIVE_ITEMS, PASSIVE_KINDS,
+    },
+    stats::{PlayerStats, Stat},
+    weapon::{
+        EvolveWeaponEvent, UpgradeWeaponEvent, Weapon, WeaponDefinitions, WeaponKind, MAX_WEAPONS,
+    },
+    GameState, Player,
+};
+
+const CHOICES_PER_LEVEL: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UpgradeKind {
+    /// Adds the weapon, or levels it up if the player already has it.
+    Weapon(WeaponKind),
+    MaxHp,
+    Recovery,
+    /// Adds the passive item, or ranks it up if the player already has it.
+    Passive(PassiveKind),
+    /// Evolves the weapon, only found in chests.
+    Evolution(WeaponKind),
+}
+
+struct StatUpgrade {
+    kind: UpgradeKind,
+    name: &'static str,
+    description: &'static str,
+    icon: &'static str,
+    icon_color: Color,
+    weight: f32,
+    // None means the upgrade can be picked forever.
+    max_picks: Option<u32>,
+}
+
+#[derive(Resource)]
+pub struct UpgradePool {
+    stat_upgrades: Vec<StatUpgrade>,
+    picks: HashMap<UpgradeKind, u32>,
+}
+
+impl UpgradePool {
+    fn is_available(&self, upgrade: &StatUpgrade) -> bool {
+        let picks = self.picks.get(&upgrade.kind).copied().unwrap_or(0);
+        match upgrade.max_picks {
+            Some(max_picks) => picks < max_picks,
+            None => true,
+        }
+    }
+}
+
+impl Default for UpgradePool {
+    fn default() -> Self {
+        Self {
+            stat_upgrades: vec![
+                StatUpgrade {
+                    kind: UpgradeKind::MaxHp,
+                    name: "Hollow Heart",
+                    description: "Increases max HP by 20.",
+                    icon: "gem.png",
+                    icon_color: Color::rgb(1.0, 0.2, 0.2),
+                    weight: 2.0,
+                    max_picks: Some(5),
+                },
+                StatUpgrade {
+                    kind: UpgradeKind::Recovery,
+                    name: "Chicken",
+                    description: "Heals 30 HP.",
+                    icon: "gem.png",
+                    icon_color: Color::rgb(0.2, 1.0, 0.2),
+                    weight: 1.0,
+                    max_picks: None,
+                },
+            ],
+            picks: HashMap::default(),
+        }
+    }
+}
+
+/// An upgrade as it is shown in the level up menu.
+#[derive(Clone)]
+pub struct UpgradeOption {
+    pub kind: UpgradeKind,
+    pub name: String,
+    pub description: String,
+    pub icon: &'static str,
+    pub icon_color: Color,
+    weight: f32,
+}
+
+/// The upgrades offered by the level up menu that is currently open.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct UpgradeChoices(pub Vec<UpgradeOption>);
+
+pub struct UpgradeChosenEvent(pub UpgradeKind);
+
+/// Every upgrade the player could currently be offered.
+pub fn upgrade_options(
+    pool: &UpgradePool,
+    weapon_definitions: &WeaponDefinitions,
+    weapon_query: &Query<&Weapon>,
+    passive_query: &Query<&PassiveItem>,
+) -> Vec<UpgradeOption> {
+    let weapon_slots_full = weapon_query.iter().len() >= MAX_WEAPONS;
+    let passive_slots_full = passive_query.iter().len() >= MAX_PASSIVE_ITEMS;
+
+    let mut options = Vec::new();
+    for definition in weapon_definitions.iter() {
+        if weapon_definitions.is_evolution(definition.kind) {
+            continue;
+        }
+        let owned_weapon = weapon_query
+            .iter()
+            .find(|weapon| weapon.kind == definition.kind);
+        let (name, description) = match owned_weapon {
+            None if weapon_slots_full => continue,
+            None => (definition.name.to_string(), definition.description),
+            Some(weapon) if weapon.level < definition.max_level() => (
+                format!("{} Lv {}", definition.name, weapon.level + 1),
+                definition.level(weapon.level + 1).description,
+            ),
+            Some(_) => continue,
+        };
+        options.push(UpgradeOption {
+            kind: UpgradeKind::Weapon(definition.kind),
+            name,
+            description: description.to_string(),
+            icon: definition.icon,
+            icon_color: definition.icon_color,
+            weight: definition.weight,
+        });
+    }
+    for kind in PASSIVE_KINDS {
+        let definition = kind.definition();
+        let owned_item = passive_query.iter().find(|item| item.kind == kind);
+        let name = match owned_item {
+            None if passive_slots_full => continue,
+            None => definition.name.to_string(),
+            Some(item) if item.rank < definition.max_rank => {
+                format!("{} Lv {}", definition.name, item.rank + 1)
+            }
+            Some(_) => continue,
+        };
+        options.push(UpgradeOption {
+            kind: UpgradeKind::Passive(kind),
+            name,
+            description: definition.description.to_string(),
+            icon: definition.icon,
+            icon_color: definition.icon_color,
+            weight: definition.weight,
+        });
+    }
+    for upgrade in pool.stat_upgrades.iter() {
+        if pool.is_available(upgrade) {
+            options.push(UpgradeOption {
+                kind: upgrade.kind,
+                name: upgrade.name.to_string(),
+                description: upgrade.description.to_string(),
+                icon: upgrade.icon,
+                icon_color: upgrade.icon_color,
+                weight: upgrade.weight,
+            });
+        }
+    }
+
+    options
+}
+
+pub fn evolution_option(
+    weapon_definitions: &WeaponDefinitions,
+    kind: WeaponKind,
+) -> Option<UpgradeOption> {
+    let evolution = weapon_definitions.get(kind).evolution.as_ref()?;
+    let evolved = weapon_definitions.get(evolution.into);
+    Some(UpgradeOption {
+        kind: UpgradeKind::Evolution(kind),
+        name: evolved.name.to_string(),
+        description: evolved.description.to_string(),
+        icon: evolved.icon,
+        icon_color: evolved.icon_color,
+        weight: 0.0,
+    })
+}
+
+pub fn choose_options(
+    options: &[UpgradeOption],
+    amount: usize,
+    rng: &mut impl Rng,
+) -> Vec<UpgradeOption> {
+    options
+        .choose_multiple_weighted(rng, amount, |option| option.weight)
+        .map(|chosen| chosen.cloned().collect())
+        .unwrap_or_default()
+}
+
+pub fn roll_upgrade_choices(
+    pool: Res<UpgradePool>,
+    weapon_definitions: Res<WeaponDefinitions>,
+    weapon_query: Query<&Weapon>,
+    passive_query: Query<&PassiveItem>,
+    player_stats_query: Query<&PlayerStats>,
+    mut choices: ResMut<UpgradeChoices>,
+) {
+    let options = upgrade_options(&pool, &weapon_definitions, &weapon_query, &passive_query);
+    let mut rng = rand::thread_rng();
+    let luck = player_stats_query
+        .iter()
+        .next()
+        .map_or(1.0, |stats| stats.get(Stat::Luck));
+    // Luck above 1 gives a chance at an extra choice.
+    let choice_count = if rng.gen::<f32>() < luck - 1.0 {
+        CHOICES_PER_LEVEL + 1
+    } else {
+        CHOICES_PER_LEVEL
+    };
+    choices.0 = choose_options(&options, choice_count, &mut rng);
+}
+
+fn apply_upgrades(
+    mut upgrade_chosen_reader: EventReader<UpgradeChosenEvent>,
+    mut pool: ResMut<UpgradePool>,
+    mut player_query: Query<&mut Player>,
+    mut upgrade_weapon_writer: EventWriter<UpgradeWeaponEvent>,
+    mut upgrade_passive_writer: EventWriter<UpgradePassiveEvent>,
+    mut evolve_weapon_writer: EventWriter<EvolveWeaponEvent>,
+) {
+    for UpgradeChosenEvent(kind) in upgrade_chosen_reader.iter() {
+        let Some(mut player) = player_query.iter_mut().next() else { return };
+        *pool.picks.entry(*kind).or_insert(0) += 1;
+
+        match kind {
+            UpgradeKind::Weapon(weapon_kind) => {
+                upgrade_weapon_writer.send(UpgradeWeaponEvent(*weapon_kind))
+            }
+            UpgradeKind::MaxHp => {
+                player.max_hp += 20;
+                player.hp += 20;
+            }
+            UpgradeKind::Recovery => player.hp = (player.hp + 30).min(player.max_hp),
+            UpgradeKind::Passive(passive_kind) => {
+                upgrade_passive_writer.send(UpgradePassiveEvent(*passive_kind))
+            }
+            UpgradeKind::Evolution(weapon_kind) => {
+                evolve_weapon_writer.send(EvolveWeaponEvent(*weapon_kind))
+            }
+        }
+    }
+}
+
+pub struct UpgradesPlugin;
+
+impl Plugin for UpgradesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UpgradePool>()
+            .init_resource::<UpgradeChoices>()
+            .add_event::<UpgradeChosenEvent>()
+            .add_system(roll_upgrade_choices.in_schedule(OnEnter(GameState::LevellingUp)))
+            .add_system(apply_upgrades);
+    }
+}
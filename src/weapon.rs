@@ -0,0 +1,642 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+use std::f32::consts::TAU;
+
+use crate::{
+    camera::MainCamera,
+    effects::DamageType,
+    enemies::Enemy,
+    passive_items::{PassiveItem, PassiveKind},
+    physics_groups,
+    simulation::{GameClock, SimulationSet},
+    stats::{PlayerStats, Stat},
+    status_effects::StatusEffect,
+    Player, WINDOW_SIZE,
+};
+
+pub const MAX_WEAPONS: usize = 6;
+// How far past the edge of the screen a projectile can fly before it is culled.
+const CULL_MARGIN: f32 = 100.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WeaponKind {
+    Fireball,
+    Cat,
+    Hellfire,
+    Zoomies,
+}
+
+pub enum Targeting {
+    NearestEnemy,
+    /// Spread evenly in a circle around the player.
+    Around,
+}
+
+pub struct ProjectileTemplate {
+    /// Weapons without a texture dress their projectiles in their own plugin.
+    pub texture: Option<&'static str>,
+    pub color: Color,
+    /// Sprite scale before the player's area is applied.
+    pub scale: f32,
+    /// Radius before the sprite scale, the collider grows with the sprite.
+    pub collider_radius: f32,
+    /// Angle in radians between projectiles fired at the same target.
+    pub spread: f32,
+    /// Seconds before a projectile fired on cooldown is despawned.
+    pub lifetime: Option<f32>,
+    /// Distance a projectile fired on cooldown can travel before it is despawned.
+    pub max_range: Option<f32>,
+    /// Applied to every enemy the projectile hits.
+    pub effect: Option<StatusEffect>,
+    /// Speed enemies are pushed away with when hit.
+    pub knockback: f32,
+    pub damage_type: DamageType,
+    /// Chance out of 1 for a hit to deal `crit_multiplier` times the damage.
+    pub crit_chance: f32,
+    pub crit_multiplier: f32,
+}
+
+pub struct WeaponLevel {
+    pub base_dmg: i32,
+    pub extra_dmg: i32,
+    pub cooldown: f32,
+    pub amount: usize,
+    pub speed: f32,
+    /// How many enemies a projectile passes through after the first one it hits.
+    pub pierce: u32,
+    pub description: &'static str,
+}
+
+impl WeaponLevel {
+    /// The level with the player's stats applied on top.
+    fn with_stats(&self, stats: &PlayerStats) -> WeaponLevel {
+        let might = stats.get(Stat::Might);
+        WeaponLevel {
+            base_dmg: (self.base_dmg as f32 * might).round() as i32,
+            extra_dmg: (self.extra_dmg as f32 * might).round() as i32,
+            cooldown: self.cooldown * stats.get(Stat::Cooldown),
+            amount: self.amount + stats.get(Stat::Amount).max(0.0) as usize,
+            speed: self.speed * stats.get(Stat::ProjectileSpeed),
+            pierce: self.pierce,
+            description: self.description,
+        }
+    }
+}
+
+pub enum WeaponBehaviour {
+    /// Called every time the cooldown finishes.
+    Cooldown(fn(&mut Commands, &FireContext)),
+    /// Called when the weapon is added or levels up, for projectiles that stay out like orbiting cats.
+    /// The projectiles from the previous level are despawned first.
+    Persistent(fn(&mut Commands, &FireContext)),
+}
+
+/// A max level weapon evolves into `into` once the player also holds `passive`.
+pub struct Evolution {
+    pub passive: PassiveKind,
+    pub into: WeaponKind,
+}
+
+pub struct WeaponDefinition {
+    pub kind: WeaponKind,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub icon: &'static str,
+    pub icon_color: Color,
+    /// How likely the weapon is to be offered when levelling up.
+    pub weight: f32,
+    pub targeting: Targeting,
+    pub projectile: ProjectileTemplate,
+    pub levels: Vec<WeaponLevel>,
+    pub behaviour: WeaponBehaviour,
+    pub evolution: Option<Evolution>,
+}
+
+impl WeaponDefinition {
+    pub fn max_level(&self) -> usize {
+        self.levels.len()
+    }
+
+    pub fn level(&self, level: usize) -> &WeaponLevel {
+        &self.levels[level.clamp(1, self.max_level()) - 1]
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct WeaponDefinitions(Vec<WeaponDefinition>);
+
+impl WeaponDefinitions {
+    pub fn get(&self, kind: WeaponKind) -> &WeaponDefinition {
+        self.0
+            .iter()
+            .find(|definition| definition.kind == kind)
+            .expect("every weapon kind is registered with add_weapon")
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &WeaponDefinition> {
+        self.0.iter()
+    }
+
+    /// Evolved weapons can only be obtained by evolving, never from levelling up.
+    pub fn is_evolution(&self, kind: WeaponKind) -> bool {
+        self.0.iter().any(
+            |definition| matches!(&definition.evolution, Some(evolution) if evolution.into == kind),
+        )
+    }
+
+    /// The first held weapon that is ready to evolve.
+    pub fn ready_evolution<'a>(
+        &self,
+        mut weapons: impl Iterator<Item = &'a Weapon>,
+        passives: &[PassiveKind],
+    ) -> Option<WeaponKind> {
+        weapons.find_map(|weapon| {
+            let definition = self.get(weapon.kind);
+            let evolution = definition.evolution.as_ref()?;
+            (weapon.level >= definition.max_level() && passives.contains(&evolution.passive))
+                .then_some(weapon.kind)
+        })
+    }
+}
+
+pub trait AddWeapon {
+    fn add_weapon(&mut self, definition: WeaponDefinition) -> &mut Self;
+}
+
+impl AddWeapon for App {
+    fn add_weapon(&mut self, definition: WeaponDefinition) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(WeaponDefinitions::default)
+            .0
+            .push(definition);
+        self
+    }
+}
+
+/// Weapons are children of the player, one entity per weapon kind.
+#[derive(Component)]
+pub struct Weapon {
+    pub kind: WeaponKind,
+    pub level: usize,
+    cooldown: Timer,
+}
+
+impl Weapon {
+    pub fn new(kind: WeaponKind) -> Self {
+        Self {
+            kind,
+            level: 1,
+            cooldown: Timer::default(),
+        }
+    }
+}
+
+/// Links a projectile back to the weapon that fired it.
+#[derive(Component)]
+pub struct WeaponProjectile {
+    pub weapon: Entity,
+}
+
+#[derive(Component)]
+pub struct Attack {
+    pub base_dmg: i32,
+    pub extra_dmg: i32,
+    /// Enemies left to hit before the attack is used up, `None` for attacks that never run out.
+    pub pierce: Option<u32>,
+    /// Seconds before the same enemy can be hit again, `None` means each enemy is only hit once.
+    pub rehit_cooldown: Option<f32>,
+    /// Enemies hit so far with the seconds left until they can be hit again.
+    pub hits: HashMap<Entity, f32>,
+    pub effect: Option<StatusEffect>,
+    pub knockback: f32,
+    pub damage_type: DamageType,
+    pub crit_chance: f32,
+    pub crit_multiplier: f32,
+}
+
+impl Attack {
+    /// The damage for one hit and whether it was a critical hit.
+    pub fn roll_damage(&self, rng: &mut impl Rng) -> (i32, bool) {
+        let damage = if self.extra_dmg > 0 {
+            self.base_dmg + rng.gen_range(0..self.extra_dmg)
+        } else {
+            self.base_dmg
+        };
+        if rng.gen::<f32>() < self.crit_chance {
+            ((damage as f32 * self.crit_multiplier).round() as i32, true)
+        } else {
+            (damage, false)
+        }
+    }
+
+    pub fn can_hit(&self, enemy: Entity) -> bool {
+        !self.hits.contains_key(&enemy)
+    }
+
+    /// Remembers the enemy and returns true when the attack is used up.
+    pub fn register_hit(&mut self, enemy: Entity) -> bool {
+        self.hits
+            .insert(enemy, self.rehit_cooldown.unwrap_or(f32::INFINITY));
+        match self.pierce.as_mut() {
+            Some(0) => true,
+            Some(pierce) => {
+                *pierce -= 1;
+                false
+            }
+            None => false,
+        }
+    }
+}
+
+#[derive(Component, Deref, DerefMut)]
+pub struct Lifetime(Timer);
+
+#[derive(Component)]
+pub struct MaxRange {
+    origin: Vec2,
+    range: f32,
+}
+
+/// Despawned once it flies too far off screen.
+#[derive(Component)]
+pub struct OffscreenCulled;
+
+pub struct UpgradeWeaponEvent(pub WeaponKind);
+
+/// Replaces a max level weapon with its evolution.
+pub struct EvolveWeaponEvent(pub WeaponKind);
+
+/// Sprite sheets for animated projectiles, filled in by the plugins of the weapons using them.
+#[derive(Resource, Default)]
+pub struct WeaponAtlases(pub HashMap<WeaponKind, Handle<TextureAtlas>>);
+
+pub struct FireContext<'a> {
+    pub weapon: Entity,
+    pub origin: Vec3,
+    /// One normalized direction per projectile.
+    pub directions: Vec<Vec2>,
+    /// The weapon level with the player's stats already applied.
+    pub stats: WeaponLevel,
+    pub projectile: &'a ProjectileTemplate,
+    /// Size multiplier for the projectiles.
+    pub area: f32,
+    /// Lifetime multiplier for the projectiles.
+    pub duration: f32,
+    pub asset_server: &'a AssetServer,
+    pub atlases: &'a WeaponAtlases,
+}
+
+impl FireContext<'_> {
+    pub fn attack(&self) -> Attack {
+        Attack {
+            base_dmg: self.stats.base_dmg,
+            extra_dmg: self.stats.extra_dmg,
+            pierce: Some(self.stats.pierce),
+            rehit_cooldown: None,
+            hits: HashMap::default(),
+            effect: self.projectile.effect,
+            knockback: self.projectile.knockback,
+            damage_type: self.projectile.damage_type,
+            crit_chance: self.projectile.crit_chance,
+            crit_multiplier: self.projectile.crit_multiplier,
+        }
+    }
+
+    /// The components every projectile needs to hit enemies, without any sprite or movement.
+    /// `attack` is usually `self.attack()`, with any changes the weapon needs.
+    pub fn projectile_bundle(&self, attack: Attack) -> impl Bundle {
+        (
+            attack,
+            WeaponProjectile {
+                weapon: self.weapon,
+            },
+            Sensor,
+            Collider::ball(self.projectile.collider_radius),
+            CollisionGroups::new(
+                physics_groups::ATTACK_GROUP,
+                physics_groups::ENEMY_GROUP | physics_groups::PROP_GROUP,
+            ),
+        )
+    }
+}
+
+/// The default cooldown hook, launches a projectile in each direction.
+pub fn fire_projectiles(commands: &mut Commands, context: &FireContext) {
+    for direction in context.directions.iter() {
+        let rotation_radians = direction.y.atan2(direction.x) + TAU / 4.0;
+        let transform = Transform {
+            translation: context.origin,
+            rotation: Quat::from_rotation_z(rotation_radians),
+            scale: Vec3::new(
+                context.area * context.projectile.scale,
+                context.area * context.projectile.scale,
+                1.0,
+            ),
+        };
+        let mut projectile = commands.spawn((
+            context.projectile_bundle(context.attack()),
+            RigidBody::Dynamic,
+            Velocity::linear(*direction * context.stats.speed),
+            OffscreenCulled,
+        ));
+        if let Some(lifetime) = context.projectile.lifetime {
+            projectile.insert(Lifetime(Timer::from_seconds(
+                lifetime * context.duration,
+                TimerMode::Once,
+            )));
+        }
+        if let Some(range) = context.projectile.max_range {
+            projectile.insert(MaxRange {
+                origin: context.origin.truncate(),
+                range,
+            });
+        }
+        match context.projectile.texture {
+            Some(texture) => projectile.insert(SpriteBundle {
+                sprite: Sprite {
+                    color: context.projectile.color,
+                    ..default()
+                },
+                texture: context.asset_server.load(texture),
+                transform,
+                ..default()
+            }),
+            None => projectile.insert(SpatialBundle::from_transform(transform)),
+        };
+    }
+}
+
+fn aim(
+    definition: &WeaponDefinition,
+    amount: usize,
+    origin: Vec2,
+    enemy_positions: &[Vec2],
+) -> Option<Vec<Vec2>> {
+    let target = match definition.targeting {
+        Targeting::NearestEnemy => {
+            enemy_positions
+                .iter()
+                .copied()
+                .reduce(|closest, position| {
+                    if position.distance(origin) < closest.distance(origin) {
+                        position
+                    } else {
+                        closest
+                    }
+                })?
+        }
+        Targeting::Around => {
+            return Some(
+                (0..amount)
+                    .map(|index| Vec2::from_angle(index as f32 * TAU / amount as f32))
+                    .collect(),
+            )
+        }
+    };
+    let direction = (target - origin).try_normalize().unwrap_or(Vec2::X);
+    let spread = definition.projectile.spread;
+    let first_offset = -spread * (amount - 1) as f32 / 2.0;
+    Some(
+        (0..amount)
+            .map(|index| Vec2::from_angle(first_offset + spread * index as f32).rotate(direction))
+            .collect(),
+    )
+}
+
+fn upgrade_weapons(
+    mut commands: Commands,
+    definitions: Res<WeaponDefinitions>,
+    mut upgrade_weapon_reader: EventReader<UpgradeWeaponEvent>,
+    player_query: Query<Entity, With<Player>>,
+    mut weapon_query: Query<&mut Weapon>,
+) {
+    let Some(player_entity) = player_query.iter().next() else { return };
+
+    // Weapons added this frame aren't spawned until the commands run, so repeats level these up.
+    let mut new_weapons: Vec<Weapon> = Vec::new();
+    for UpgradeWeaponEvent(kind) in upgrade_weapon_reader.iter() {
+        let max_level = definitions.get(*kind).max_level();
+        if let Some(mut weapon) = weapon_query.iter_mut().find(|weapon| weapon.kind == *kind) {
+            weapon.level = (weapon.level + 1).min(max_level);
+        } else if let Some(weapon) = new_weapons.iter_mut().find(|weapon| weapon.kind == *kind) {
+            weapon.level = (weapon.level + 1).min(max_level);
+        } else {
+            new_weapons.push(Weapon::new(*kind));
+        }
+    }
+    if new_weapons.is_empty() {
+        return;
+    }
+    commands.entity(player_entity).with_children(|parent| {
+        for weapon in new_weapons {
+            parent.spawn(weapon);
+        }
+    });
+}
+
+fn evolve_weapons(
+    mut commands: Commands,
+    definitions: Res<WeaponDefinitions>,
+    mut evolve_weapon_reader: EventReader<EvolveWeaponEvent>,
+    mut weapon_query: Query<(Entity, &mut Weapon)>,
+    passive_query: Query<&PassiveItem>,
+    projectile_query: Query<(Entity, &WeaponProjectile)>,
+) {
+    let passives: Vec<PassiveKind> = passive_query.iter().map(|item| item.kind).collect();
+
+    for EvolveWeaponEvent(kind) in evolve_weapon_reader.iter() {
+        let Some((weapon_entity, mut weapon)) = weapon_query
+            .iter_mut()
+            .find(|(_, weapon)| weapon.kind == *kind)
+        else {
+            continue;
+        };
+        if definitions.ready_evolution(std::iter::once(&*weapon), &passives) != Some(*kind) {
+            continue;
+        }
+        let Some(evolution) = &definitions.get(*kind).evolution else { continue };
+
+        // Persistent weapons swap out their own projectiles when they change.
+        if let WeaponBehaviour::Cooldown(_) = definitions.get(evolution.into).behaviour {
+            for (projectile_entity, projectile) in projectile_query.iter() {
+                if projectile.weapon == weapon_entity {
+                    commands.entity(projectile_entity).despawn();
+                }
+            }
+        }
+        *weapon = Weapon::new(evolution.into);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn fire_weapons(
+    mut commands: Commands,
+    time: Res<GameClock>,
+    asset_server: Res<AssetServer>,
+    atlases: Res<WeaponAtlases>,
+    definitions: Res<WeaponDefinitions>,
+    mut weapon_query: Query<(Entity, &mut Weapon)>,
+    player_query: Query<(&Transform, &PlayerStats), With<Player>>,
+    enemy_transform_query: Query<&Transform, With<Enemy>>,
+) {
+    let Some((player_transform, player_stats)) = player_query.iter().next() else { return };
+    let enemy_positions: Vec<Vec2> = enemy_transform_query
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .collect();
+
+    for (weapon_entity, mut weapon) in weapon_query.iter_mut() {
+        let definition = definitions.get(weapon.kind);
+        let WeaponBehaviour::Cooldown(fire) = definition.behaviour else { continue };
+        let stats = definition.level(weapon.level).with_stats(player_stats);
+
+        let cooldown = std::time::Duration::from_secs_f32(stats.cooldown);
+        if weapon.cooldown.duration() != cooldown {
+            weapon.cooldown.set_duration(cooldown);
+            weapon.cooldown.set_mode(TimerMode::Repeating);
+        }
+        weapon.cooldown.tick(time.delta());
+        if !weapon.cooldown.just_finished() {
+            continue;
+        }
+
+        let origin = player_transform.translation.truncate();
+        let Some(directions) = aim(definition, stats.amount, origin, &enemy_positions) else { continue };
+        fire(
+            &mut commands,
+            &FireContext {
+                weapon: weapon_entity,
+                origin: origin.extend(1.0),
+                directions,
+                stats,
+                projectile: &definition.projectile,
+                area: player_stats.get(Stat::Area),
+                duration: player_stats.get(Stat::Duration),
+                asset_server: &asset_server,
+                atlases: &atlases,
+            },
+        );
+    }
+}
+
+fn refresh_persistent_weapons(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    atlases: Res<WeaponAtlases>,
+    definitions: Res<WeaponDefinitions>,
+    weapon_query: Query<(Entity, Ref<Weapon>)>,
+    projectile_query: Query<(Entity, &WeaponProjectile)>,
+    player_query: Query<(&Transform, Ref<PlayerStats>), With<Player>>,
+) {
+    let Some((player_transform, player_stats)) = player_query.iter().next() else { return };
+
+    for (weapon_entity, weapon) in weapon_query.iter() {
+        // Stat changes can grow the projectiles or add more of them, so those refresh everything.
+        if !weapon.is_changed() && !player_stats.is_changed() {
+            continue;
+        }
+        let definition = definitions.get(weapon.kind);
+        let WeaponBehaviour::Persistent(fire) = definition.behaviour else { continue };
+        let stats = definition.level(weapon.level).with_stats(&player_stats);
+
+        for (projectile_entity, projectile) in projectile_query.iter() {
+            if projectile.weapon == weapon_entity {
+                commands.entity(projectile_entity).despawn();
+            }
+        }
+
+        let origin = player_transform.translation.truncate();
+        let Some(directions) = aim(definition, stats.amount, origin, &[]) else { continue };
+        fire(
+            &mut commands,
+            &FireContext {
+                weapon: weapon_entity,
+                origin: origin.extend(1.0),
+                directions,
+                stats,
+                projectile: &definition.projectile,
+                area: player_stats.get(Stat::Area),
+                duration: player_stats.get(Stat::Duration),
+                asset_server: &asset_server,
+                atlases: &atlases,
+            },
+        );
+    }
+}
+
+fn tick_attack_hits(time: Res<GameClock>, mut attack_query: Query<&mut Attack>) {
+    let delta_seconds = time.delta_seconds();
+    for mut attack in attack_query.iter_mut() {
+        attack
+            .hits
+            .drain_filter(|_k, v| {
+                *v -= delta_seconds;
+                *v <= 0.0
+            })
+            .for_each(drop);
+    }
+}
+
+type ExpiringProjectile<'a> = (
+    Entity,
+    &'a Transform,
+    Option<&'a mut Lifetime>,
+    Option<&'a MaxRange>,
+    Option<&'a OffscreenCulled>,
+);
+type ExpiringFilter = Or<(With<Lifetime>, With<MaxRange>, With<OffscreenCulled>)>;
+
+fn expire_projectiles(
+    mut commands: Commands,
+    time: Res<GameClock>,
+    mut projectile_query: Query<ExpiringProjectile<'_>, ExpiringFilter>,
+    camera_query: Query<&Transform, With<MainCamera>>,
+) {
+    let camera_position = camera_query
+        .iter()
+        .next()
+        .map_or(Vec2::ZERO, |transform| transform.translation.truncate());
+    let visible_extent = WINDOW_SIZE / 2.0 + CULL_MARGIN;
+
+    for (entity, transform, lifetime, max_range, culled) in projectile_query.iter_mut() {
+        let position = transform.translation.truncate();
+        let mut timed_out = false;
+        if let Some(mut lifetime) = lifetime {
+            lifetime.tick(time.delta());
+            timed_out = lifetime.finished();
+        }
+        let out_of_range = match max_range {
+            Some(max_range) => position.distance(max_range.origin) > max_range.range,
+            None => false,
+        };
+        let offset = position - camera_position;
+        let off_screen = culled.is_some()
+            && (offset.x.abs() > visible_extent || offset.y.abs() > visible_extent);
+        if timed_out || out_of_range || off_screen {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub struct WeaponPlugin;
+
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WeaponDefinitions>()
+            .init_resource::<WeaponAtlases>()
+            .add_event::<UpgradeWeaponEvent>()
+            .add_event::<EvolveWeaponEvent>()
+            .add_system(upgrade_weapons)
+            .add_system(evolve_weapons)
+            .add_system(
+                refresh_persistent_weapons
+                    .after(upgrade_weapons)
+                    .after(evolve_weapons),
+            )
+            .add_systems(
+                (fire_weapons, tick_attack_hits, expire_projectiles).in_set(SimulationSet),
+            );
+    }
+}